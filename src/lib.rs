@@ -1,22 +1,49 @@
 use std::{
-    fmt::Debug,
-    mem::ManuallyDrop,
+    backtrace::Backtrace,
+    borrow::Cow,
+    cell::Cell,
+    collections::TryReserveError,
+    ffi::CString,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    mem::{ManuallyDrop, MaybeUninit},
     ops::{Deref, DerefMut},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll},
 };
 
+/// Glob-importable names. [`Result`] is exported as `FfiResult` so that it does
+/// not shadow the std `Result` from the language prelude.
+pub mod prelude {
+    pub use crate::{Result as FfiResult, ResultKind, ShrinkToFit, Visitor};
+}
+
 /// FFI-compatibe and ABI-stable analogue for [`core::result::Result`].
 ///
 /// Can be freely converted to and from the core Result.
+///
+/// With the `checked` feature a redundant copy of the tag is appended after
+/// the data, which changes the layout. Every method that reads the payload,
+/// including `Clone`, `Debug` and `Drop`, first compares the tag with that copy:
+/// [`Result::try_into_result`] reports a mismatch as [`CorruptTag`], `Drop`
+/// leaks the payload and all other accessors panic. Methods that only read the
+/// tag, such as [`Result::is_ok`], and the raw [`Result::data`] accessors are not checked.
 #[repr(C)]
 pub struct Result<T, E> {
     kind: ResultKind,
     data: ResultData<T, E>,
+    #[cfg(feature = "checked")]
+    check: ResultKind,
 }
 
 impl<T, E> Result<T, E> {
     pub const fn new_ok(t: T) -> Self {
         Self {
             kind: ResultKind::Ok,
+            #[cfg(feature = "checked")]
+            check: ResultKind::Ok,
             data: ResultData {
                 ok: ManuallyDrop::new(t),
             },
@@ -25,6 +52,8 @@ impl<T, E> Result<T, E> {
     pub const fn new_err(e: E) -> Self {
         Self {
             kind: ResultKind::Err,
+            #[cfg(feature = "checked")]
+            check: ResultKind::Err,
             data: ResultData {
                 err: ManuallyDrop::new(e),
             },
@@ -42,8 +71,25 @@ impl<T, E> Result<T, E> {
         &self.kind
     }
 
+    /// The variant by value, for printing results whose payloads aren't `Debug`.
+    ///
+    /// [`Debug`] for [`Result`] needs both `T: Debug` and `E: Debug`; without
+    /// specialization there is no fallback impl, so use `{:?}` on this instead.
+    pub const fn debug_kind(&self) -> ResultKind {
+        self.kind
+    }
+
+    /// Raw tag value: 0 for `Ok`, 1 for `Err`.
+    pub const fn discriminant(&self) -> u8 {
+        self.kind as u8
+    }
+
     /// # Safety
     /// Cannot guarantee that the user will preserve correct kind-data relationship.
+    ///
+    /// With the `checked` feature, the redundant tag is not updated through this
+    /// reference, so any change of the kind makes the payload accessors panic,
+    /// see [`Result`].
     pub const unsafe fn kind_mut(&mut self) -> &mut ResultKind {
         &mut self.kind
     }
@@ -59,9 +105,12 @@ impl<T, E> Result<T, E> {
     }
 
     pub fn as_ref(&self) -> Result<&T, &E> {
+        let kind = self.checked_kind();
         Result {
-            kind: self.kind,
-            data: match self.kind {
+            kind,
+            #[cfg(feature = "checked")]
+            check: self.check,
+            data: match kind {
                 ResultKind::Ok => {
                     let inner = unsafe { &self.data.ok };
                     ResultData {
@@ -79,9 +128,12 @@ impl<T, E> Result<T, E> {
     }
 
     pub fn as_mut(&mut self) -> Result<&mut T, &mut E> {
+        let kind = self.checked_kind();
         Result {
-            kind: self.kind,
-            data: match self.kind {
+            kind,
+            #[cfg(feature = "checked")]
+            check: self.check,
+            data: match kind {
                 ResultKind::Ok => {
                     let inner = unsafe { &mut self.data.ok };
                     ResultData {
@@ -99,7 +151,7 @@ impl<T, E> Result<T, E> {
     }
 
     pub fn ok(self) -> Option<T> {
-        let kind = self.kind;
+        let kind = self.checked_kind();
         // SAFETY: we only read the union bits, which are valid for either variant
         let mut data = unsafe { std::ptr::read(&self.data) };
         std::mem::forget(self);
@@ -113,7 +165,7 @@ impl<T, E> Result<T, E> {
         }
     }
     pub fn err(self) -> Option<E> {
-        let kind = self.kind;
+        let kind = self.checked_kind();
         // SAFETY: we only read the union bits, which are valid for either variant
         let mut data = unsafe { std::ptr::read(&self.data) };
         std::mem::forget(self);
@@ -127,7 +179,7 @@ impl<T, E> Result<T, E> {
         }
     }
     pub fn into_result(self) -> core::result::Result<T, E> {
-        let kind = self.kind;
+        let kind = self.checked_kind();
         // SAFETY: we only read the union bits, which are valid for either variant
         let data = unsafe { std::ptr::read(&self.data) };
         std::mem::forget(self);
@@ -141,6 +193,36 @@ impl<T, E> Result<T, E> {
             }
         }
     }
+    /// Like [`Result::into_result`], but with the `checked` feature returns
+    /// [`CorruptTag`] instead of reading the wrong variant, leaking the payload.
+    /// Without the feature this is always `Ok(self.into_result())`.
+    pub fn try_into_result(self) -> core::result::Result<core::result::Result<T, E>, CorruptTag> {
+        #[cfg(feature = "checked")]
+        if !self.is_tag_consistent() {
+            std::mem::forget(self);
+            return Err(CorruptTag);
+        }
+        Ok(self.into_result())
+    }
+
+    /// The tag, asserting with the `checked` feature that it matches its redundant copy.
+    /// Every accessor that reads the payload goes through this.
+    fn checked_kind(&self) -> ResultKind {
+        #[cfg(feature = "checked")]
+        assert!(
+            self.is_tag_consistent(),
+            "result tag is inconsistent with its data"
+        );
+        self.kind
+    }
+
+    #[cfg(feature = "checked")]
+    fn is_tag_consistent(&self) -> bool {
+        // SAFETY: the tag is an initialized byte, read it without assuming a valid `ResultKind`
+        let tag = unsafe { (&raw const self.kind).cast::<u8>().read() };
+        ResultKind::from_tag(tag) == Ok(self.check)
+    }
+
     pub fn from_result(result: core::result::Result<T, E>) -> Self {
         match result {
             Ok(ok) => Self::new_ok(ok),
@@ -154,23 +236,640 @@ impl<T, E> Result<T, E> {
     pub fn map_err<E2>(self, op: impl FnOnce(E) -> E2) -> Result<T, E2> {
         self.into_result().map_err(op).into()
     }
+
+    /// Captures a [`Backtrace`] alongside the error. Nothing is captured on `Ok`.
+    pub fn capture_backtrace(self) -> Result<T, (Backtrace, E)> {
+        self.map_err(|err| (Backtrace::capture(), err))
+    }
+
+    /// Boxes the active payload and returns the tag with owning pointers.
+    /// The pointer selected by the tag owns a `Box` allocation, the other one is null.
+    pub fn into_c_tagged(self) -> (ResultKind, *mut T, *mut E) {
+        match self.into_result() {
+            Ok(ok) => (
+                ResultKind::Ok,
+                Box::into_raw(Box::new(ok)),
+                std::ptr::null_mut(),
+            ),
+            Err(err) => (
+                ResultKind::Err,
+                std::ptr::null_mut(),
+                Box::into_raw(Box::new(err)),
+            ),
+        }
+    }
+
+    /// # Safety
+    /// The pointer selected by `kind` must come from [`Result::into_c_tagged`]
+    /// and must not be used afterwards. The other pointer is ignored.
+    pub unsafe fn from_c_tagged(kind: ResultKind, ok: *mut T, err: *mut E) -> Self {
+        match kind {
+            ResultKind::Ok => Self::new_ok(*unsafe { Box::from_raw(ok) }),
+            ResultKind::Err => Self::new_err(*unsafe { Box::from_raw(err) }),
+        }
+    }
+
+    pub fn map_ref<U>(&self, op: impl FnOnce(&T) -> U) -> Option<U> {
+        self.as_ref().ok().map(op)
+    }
+
+    pub fn as_ok_slice<U>(&self) -> Option<&[U]>
+    where
+        T: AsRef<[U]>,
+    {
+        self.as_ref().ok().map(AsRef::as_ref)
+    }
+
+    /// Replaces the whole result with `Ok(value)`, dropping the previous payload.
+    pub fn set_ok(self, value: T) -> Self {
+        drop(self);
+        Self::new_ok(value)
+    }
+    /// Replaces the whole result with `Err(value)`, dropping the previous payload.
+    pub fn set_err(self, value: E) -> Self {
+        drop(self);
+        Self::new_err(value)
+    }
+
+    /// Merges two results, first success wins: returns `self` if it is `Ok`,
+    /// otherwise `other` if it is `Ok`, otherwise `self`'s error.
+    /// The result that is not returned is dropped.
+    pub fn coalesce(self, other: Self) -> Self {
+        if self.is_ok() || other.is_err() {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Size of the larger of the two payloads.
+    pub const fn max_payload_size() -> usize {
+        let ok = std::mem::size_of::<T>();
+        let err = std::mem::size_of::<E>();
+        if ok > err { ok } else { err }
+    }
+    /// Size of the whole result, including the tag and padding.
+    pub const fn total_size() -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    pub fn from_fn(f: impl FnOnce() -> core::result::Result<T, E>) -> Self {
+        Self::from_result(f())
+    }
+
+    /// Clones the result if it is `Ok`, without requiring `E: Clone`.
+    pub fn clone_if_ok(&self) -> Option<Self>
+    where
+        T: Clone,
+    {
+        self.map_ref(|ok| Self::new_ok(ok.clone()))
+    }
+    /// Clones the result if it is `Err`, without requiring `T: Clone`.
+    pub fn clone_if_err(&self) -> Option<Self>
+    where
+        E: Clone,
+    {
+        self.as_ref().err().map(|err| Self::new_err(err.clone()))
+    }
+
+    /// Moves the union to the heap and returns it with the tag kept separately.
+    /// Ownership passes to the returned [`RawResult`], reclaim it with [`Result::from_raw`].
+    pub fn into_raw(self) -> RawResult<T, E> {
+        let kind = self.checked_kind();
+        // SAFETY: we only read the union bits, which are valid for either variant
+        let data = unsafe { std::ptr::read(&self.data) };
+        std::mem::forget(self);
+
+        RawResult {
+            kind,
+            data: Box::into_raw(Box::new(data)),
+        }
+    }
+
+    /// # Safety
+    /// `raw` must come from [`Result::into_raw`] and must not be used afterwards.
+    pub unsafe fn from_raw(raw: RawResult<T, E>) -> Self {
+        Self {
+            kind: raw.kind,
+            #[cfg(feature = "checked")]
+            check: raw.kind,
+            data: *unsafe { Box::from_raw(raw.data) },
+        }
+    }
+
+    pub fn convert<T2, E2>(self) -> Result<T2, E2>
+    where
+        T: Into<T2>,
+        E: Into<E2>,
+    {
+        match self.into_result() {
+            Ok(ok) => Result::new_ok(ok.into()),
+            Err(err) => Result::new_err(err.into()),
+        }
+    }
+
+    pub fn log_err(self, logger: &impl Fn(&E)) -> Self {
+        if let Some(err) = self.as_ref().err() {
+            logger(err);
+        }
+        self
+    }
+    pub fn log_ok(self, logger: &impl Fn(&T)) -> Self {
+        if let Some(ok) = self.as_ref().ok() {
+            logger(ok);
+        }
+        self
+    }
+
+    /// Returns `op`'s value on `Ok`, or `default` if the result is `Err` or `op` declines.
+    pub fn ok_map_or<U>(self, default: U, op: impl FnOnce(T) -> Option<U>) -> U {
+        self.ok().and_then(op).unwrap_or(default)
+    }
+
+    pub fn ok_mut(&mut self) -> Option<&mut T> {
+        self.as_mut().ok()
+    }
+    pub fn err_mut(&mut self) -> Option<&mut E> {
+        self.as_mut().err()
+    }
+
+    /// Compares two results with custom payload comparators. Different variants are never equal.
+    pub fn eq_by(
+        &self,
+        other: &Self,
+        ok_eq: impl Fn(&T, &T) -> bool,
+        err_eq: impl Fn(&E, &E) -> bool,
+    ) -> bool {
+        match (self.as_ref().into_result(), other.as_ref().into_result()) {
+            (Ok(a), Ok(b)) => ok_eq(a, b),
+            (Err(a), Err(b)) => err_eq(a, b),
+            (Ok(_), Err(_)) => false,
+            (Err(_), Ok(_)) => false,
+        }
+    }
+
+    /// Maps `Ok` through `op` and `Err` through `default`, either of which may fail.
+    pub fn map_or_else_try<U>(
+        self,
+        default: impl FnOnce(E) -> Result<U, E>,
+        op: impl FnOnce(T) -> Result<U, E>,
+    ) -> Result<U, E> {
+        match self.into_result() {
+            Ok(ok) => op(ok),
+            Err(err) => default(err),
+        }
+    }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_ref().ok() == Some(x)
+    }
+    pub fn contains_err(&self, e: &E) -> bool
+    where
+        E: PartialEq,
+    {
+        self.as_ref().err() == Some(e)
+    }
+
+    pub fn split(self) -> (Option<T>, Option<E>) {
+        let kind = self.checked_kind();
+        // SAFETY: we only read the union bits, which are valid for either variant
+        let data = unsafe { std::ptr::read(&self.data) };
+        std::mem::forget(self);
+
+        match kind {
+            ResultKind::Ok => (Some(unsafe { ManuallyDrop::into_inner(data.ok) }), None),
+            ResultKind::Err => (None, Some(unsafe { ManuallyDrop::into_inner(data.err) })),
+        }
+    }
+
+    pub fn unwrap_or_else(self, op: impl FnOnce(E) -> T) -> T {
+        self.into_result().unwrap_or_else(op)
+    }
+    /// Returns the `Ok` value or recovers one from the error.
+    /// Same as [`Result::unwrap_or_else`].
+    ///
+    /// ```
+    /// let res: ffi_result::Result<u32, &str> = ffi_result::Result::new_err("oops");
+    /// assert_eq!(res.recover(|err| err.len() as u32), 4);
+    /// ```
+    pub fn recover(self, f: impl FnOnce(E) -> T) -> T {
+        self.unwrap_or_else(f)
+    }
+
+    /// Projects the pin into the active payload.
+    ///
+    /// Pinning is structural for both payloads: a pinned [`Result`] never moves its
+    /// payload, it is only dropped in place, and `Result` is `Unpin` only if both
+    /// `T` and `E` are.
+    pub fn as_pin_ref(self: Pin<&Self>) -> Result<Pin<&T>, Pin<&E>> {
+        // SAFETY: pinning is structural, see above
+        unsafe {
+            let inner = self.get_ref().as_ref();
+            match inner.into_result() {
+                Ok(ok) => Result::new_ok(Pin::new_unchecked(ok)),
+                Err(err) => Result::new_err(Pin::new_unchecked(err)),
+            }
+        }
+    }
+    /// Projects the pin into the active payload, see [`Result::as_pin_ref`].
+    pub fn as_pin_mut(self: Pin<&mut Self>) -> Result<Pin<&mut T>, Pin<&mut E>> {
+        // SAFETY: pinning is structural, and the payload is not moved out of the reference
+        unsafe {
+            let inner = self.get_unchecked_mut().as_mut();
+            match inner.into_result() {
+                Ok(ok) => Result::new_ok(Pin::new_unchecked(ok)),
+                Err(err) => Result::new_err(Pin::new_unchecked(err)),
+            }
+        }
+    }
+
+    /// Asserts at compile time that `Result<T, E>` and `Result<T2, E2>` have the same
+    /// size and alignment, e.g. before transmuting between them across FFI.
+    ///
+    /// The assertion is evaluated when the call is monomorphized, so a mismatch
+    /// fails `cargo build` but is not reported by `cargo check`.
+    pub const fn assert_same_layout<T2, E2>() {
+        const {
+            assert!(
+                std::mem::size_of::<Self>() == std::mem::size_of::<Result<T2, E2>>(),
+                "Result instantiations differ in size"
+            );
+            assert!(
+                std::mem::align_of::<Self>() == std::mem::align_of::<Result<T2, E2>>(),
+                "Result instantiations differ in alignment"
+            );
+        }
+    }
+
+    /// Reinterprets the error type in place, without moving the payload.
+    ///
+    /// # Safety
+    /// `E` and `E2` must have identical layout, and every valid `E` must be a valid `E2`
+    /// (e.g. a `#[repr(transparent)]` newtype). Mismatched result sizes fail the build,
+    /// see [`Result::assert_same_layout`].
+    pub unsafe fn transmute_err<E2>(self) -> Result<T, E2> {
+        Self::assert_same_layout::<T, E2>();
+        let this = ManuallyDrop::new(self);
+        unsafe { std::mem::transmute_copy(this.deref()) }
+    }
+
+    pub fn accept(&self, v: &mut impl Visitor<T, E>) {
+        match self.as_ref().into_result() {
+            Ok(ok) => v.on_ok(ok),
+            Err(err) => v.on_err(err),
+        }
+    }
+
+    /// [`Result::map_err`] through a mapper chosen at runtime.
+    pub fn map_err_dyn(self, f: &dyn Fn(E) -> E) -> Result<T, E> {
+        self.map_err(f)
+    }
+
+    /// Moves the result into an [`Arc`] for read-only sharing.
+    /// The `Arc` can be sent across threads when both `T` and `E` are `Send + Sync`.
+    pub fn into_shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Maps an error enum to its C error code.
+    pub fn err_as_i32(self) -> Result<T, i32>
+    where
+        E: Into<i32>,
+    {
+        self.map_err(Into::into)
+    }
+
+    /// Drops the active payload in place without freeing the memory behind `ptr`,
+    /// e.g. for results allocated in a foreign arena.
+    ///
+    /// # Safety
+    /// `ptr` must be non-null, aligned, point to a valid initialized result and be
+    /// valid for writes with no other live references to it. Afterwards the
+    /// pointee is dropped and must not be used or dropped again.
+    pub unsafe fn drop_contents(ptr: *mut Self) {
+        unsafe { std::ptr::drop_in_place(ptr) }
+    }
+
+    /// Combines two `Ok` values with `f`, or returns the first error.
+    /// If `self` is `Err`, `other` is dropped unused.
+    pub fn combine_with<U, R>(
+        self,
+        other: Result<U, E>,
+        f: impl FnOnce(T, U) -> R,
+    ) -> Result<R, E> {
+        match (self.into_result(), other.into_result()) {
+            (Ok(a), Ok(b)) => Result::new_ok(f(a, b)),
+            (Err(err), _) | (Ok(_), Err(err)) => Result::new_err(err),
+        }
+    }
+
+    /// Exchanges the tags and payloads of two results in place. Nothing is dropped.
+    pub fn swap_with(&mut self, other: &mut Self) {
+        std::mem::swap(self, other)
+    }
+
+    pub fn map_both_ref<U, V>(
+        &self,
+        ok_op: impl FnOnce(&T) -> U,
+        err_op: impl FnOnce(&E) -> V,
+    ) -> core::result::Result<U, V> {
+        self.as_ref().into_result().map(ok_op).map_err(err_op)
+    }
+
+    /// Pointer to the `Ok` payload, or null for `Err`. Valid while `self` is borrowed.
+    pub fn ok_as_ptr_or_null(&self) -> *const T {
+        self.as_ref()
+            .ok()
+            .map_or(std::ptr::null(), |ok| ok as *const T)
+    }
+
+    #[inline]
+    pub fn ok_or_err_ref(&self) -> core::result::Result<&T, &E> {
+        unsafe {
+            match self.checked_kind() {
+                ResultKind::Ok => Ok(self.data.ok.deref()),
+                ResultKind::Err => Err(self.data.err.deref()),
+            }
+        }
+    }
+
+    /// Returns the `Ok` value, or passes the error to `report` and aborts the
+    /// process instead of panicking, for FFI entry points that must not unwind.
+    pub fn unwrap_or_report(self, report: impl FnOnce(&E)) -> T {
+        match self.into_result() {
+            Ok(ok) => ok,
+            Err(err) => {
+                report(&err);
+                std::process::abort()
+            }
+        }
+    }
+
+    /// Splits the result into its tag and two out-parameters. For tag 0 (`Ok`)
+    /// only the `T` slot is initialized, for tag 1 (`Err`) only the `E` slot.
+    pub fn into_maybe_uninit(self) -> (u8, MaybeUninit<T>, MaybeUninit<E>) {
+        match self.into_result() {
+            Ok(ok) => (
+                ResultKind::Ok as u8,
+                MaybeUninit::new(ok),
+                MaybeUninit::uninit(),
+            ),
+            Err(err) => (
+                ResultKind::Err as u8,
+                MaybeUninit::uninit(),
+                MaybeUninit::new(err),
+            ),
+        }
+    }
+
+    /// The `Ok` value as a one-element slice, or an empty slice for `Err`.
+    pub fn as_slice(&self) -> &[T] {
+        match self.as_ref().ok() {
+            Some(ok) => std::slice::from_ref(ok),
+            None => &[],
+        }
+    }
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match self.ok_mut() {
+            Some(ok) => std::slice::from_mut(ok),
+            None => &mut [],
+        }
+    }
+
+    /// Compares `Ok` values, treating any two errors as equal.
+    pub fn eq_ignore_err(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.eq_by(other, T::eq, |_, _| true)
+    }
+
+    pub fn pipe<R>(self, f: impl FnOnce(Self) -> R) -> R {
+        f(self)
+    }
+
+    /// Forcibly sets the tag while keeping the payload bytes. Meant for negative
+    /// tests of the `checked` feature, where the relabeled result is reported by
+    /// [`Result::try_into_result`] and trips the consistency assertion of [`Result::into_result`].
+    ///
+    /// # Safety
+    /// Relabeling to the other variant leaves a result whose payload must not be read.
+    /// With the `checked` feature, the only sound uses of it are
+    /// [`Result::try_into_result`], [`Result::into_result`] (which panics) and
+    /// dropping it (which leaks the payload). Without the feature, it is UB as
+    /// soon as the payload is read or dropped.
+    pub unsafe fn reinterpret_kind(mut self, kind: ResultKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Maps the `Ok` value with `op`, or aborts the process on `Err`.
+    pub fn map_or_abort<U>(self, op: impl FnOnce(T) -> U) -> U {
+        match self.into_result() {
+            Ok(ok) => op(ok),
+            Err(_) => std::process::abort(),
+        }
+    }
+
+    /// On `Err`, lets `op` either recover to `Ok` or fail with a new error type.
+    pub fn try_map_err<E2>(self, op: impl FnOnce(E) -> Result<T, E2>) -> Result<T, E2> {
+        match self.into_result() {
+            Ok(ok) => Result::new_ok(ok),
+            Err(err) => op(err),
+        }
+    }
+
+    /// Maps the `Ok` value to `Some`, or passes the error to `on_err` and returns `None`.
+    pub fn map_or_log_err<U>(self, op: impl FnOnce(T) -> U, on_err: impl FnOnce(&E)) -> Option<U> {
+        match self.into_result() {
+            Ok(ok) => Some(op(ok)),
+            Err(err) => {
+                on_err(&err);
+                None
+            }
+        }
+    }
+
+    /// Boxes whichever payload is present. Exactly one of the options is `Some`.
+    pub fn into_boxed_pair(self) -> (Option<Box<T>>, Option<Box<E>>) {
+        let (ok, err) = self.split();
+        (ok.map(Box::new), err.map(Box::new))
+    }
 }
 impl<T: Debug, E> Result<T, E> {
+    #[track_caller]
     pub fn unwrap_err(self) -> E {
         self.into_result().unwrap_err()
     }
+    #[track_caller]
     pub fn expect_err(self, msg: &str) -> E {
         self.into_result().expect_err(msg)
     }
 }
 impl<T, E: Debug> Result<T, E> {
+    #[track_caller]
     pub fn unwrap(self) -> T {
         self.into_result().unwrap()
     }
+    #[track_caller]
     pub fn expect(self, msg: &str) -> T {
         self.into_result().expect(msg)
     }
 }
+impl<T: ShrinkToFit, E> Result<T, E> {
+    pub fn shrink_ok(&mut self) {
+        if let Some(ok) = self.as_mut().ok() {
+            ok.shrink_to_fit();
+        }
+    }
+}
+impl<T, E: Display> Result<T, E> {
+    /// Formats the error into a [`CString`]. Interior NUL bytes are replaced
+    /// with U+FFFD so the message is never truncated or rejected.
+    pub fn err_to_cstring(self) -> Option<CString> {
+        self.err().map(|err| display_to_cstring(&err))
+    }
+
+    pub fn stringify_err(self) -> Result<T, String> {
+        self.map_err(|err| err.to_string())
+    }
+
+    /// The formatted error, or the borrowed marker `"ok"` for `Ok`.
+    pub fn err_to_cow(self) -> Cow<'static, str> {
+        match self.err() {
+            Some(err) => Cow::Owned(err.to_string()),
+            None => Cow::Borrowed("ok"),
+        }
+    }
+}
+impl<T: Display, E> Result<T, E> {
+    /// Formats the value into a [`CString`]. Interior NUL bytes are replaced
+    /// with U+FFFD, same as [`Result::err_to_cstring`].
+    pub fn ok_to_cstring(self) -> Option<CString> {
+        self.ok().map(|ok| display_to_cstring(&ok))
+    }
+}
+impl<T, E> Result<T, Result<E, E>> {
+    /// Collapses the error layer: both `Err(Ok(e))` and `Err(Err(e))` become `Err(e)`.
+    pub fn flatten_err(self) -> Result<T, E> {
+        self.map_err(|err| match err.into_result() {
+            Ok(err) | Err(err) => err,
+        })
+    }
+}
+impl<T: Into<i64>, E: Into<i64>> Result<T, E> {
+    /// Encodes the result as a C-style status code: `Ok(v)` becomes `v` and
+    /// `Err(e)` becomes `-(e + 1)`, so `Err(0)` is `-1` and `Err(i64::MAX)` is `i64::MIN`.
+    ///
+    /// # Panics
+    /// If the `Ok` value or the error is negative, since it could not be decoded back.
+    #[track_caller]
+    pub fn into_code(self) -> i64 {
+        match self.into_result() {
+            Ok(ok) => {
+                let ok = ok.into();
+                assert!(ok >= 0, "Ok value {ok} cannot be encoded as a status code");
+                ok
+            }
+            Err(err) => {
+                let err = err.into();
+                assert!(err >= 0, "error {err} cannot be encoded as a status code");
+                !err
+            }
+        }
+    }
+}
+impl Result<i64, i64> {
+    /// Decodes a status code produced by [`Result::into_code`]:
+    /// non-negative codes are `Ok`, negative codes are `Err(-code - 1)`.
+    pub const fn from_code(code: i64) -> Self {
+        if code >= 0 {
+            Self::new_ok(code)
+        } else {
+            Self::new_err(!code)
+        }
+    }
+}
+impl<T, E: Copy> Result<T, E> {
+    /// Borrows the `Ok` value but copies the error, for small status-code errors.
+    pub fn as_ref_copy_err(&self) -> Result<&T, E> {
+        self.as_ref().map_err(|err| *err)
+    }
+}
+impl<T: Default, E> Result<T, E> {
+    /// Takes the result out of the slot, leaving `Ok(T::default())` in its place.
+    pub fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::new_ok(T::default()))
+    }
+}
+impl<T, E> Result<Option<T>, E> {
+    /// `Ok(Some(t))` becomes `Ok(t)`, `Ok(None)` becomes `Ok(default)`, `Err` passes through.
+    pub fn ok_flatten_or(self, default: T) -> Result<T, E> {
+        self.map(|ok| ok.unwrap_or(default))
+    }
+}
+impl<T, E: std::error::Error + Send + Sync + 'static> Result<T, E> {
+    pub fn erase_err(self) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        self.map_err(Into::into)
+    }
+}
+impl<U, E> Result<Vec<U>, E> {
+    pub fn new_ok_vec_with_capacity(cap: usize) -> Self {
+        Self::new_ok(Vec::with_capacity(cap))
+    }
+
+    /// Tries to reserve capacity in the `Ok` vector, does nothing for `Err`.
+    /// Allocation failure is returned instead of aborting.
+    pub fn try_reserve_ok(
+        &mut self,
+        additional: usize,
+    ) -> core::result::Result<(), TryReserveError> {
+        match self.ok_mut() {
+            Some(ok) => ok.try_reserve(additional),
+            None => Ok(()),
+        }
+    }
+}
+impl<E> Result<String, E> {
+    pub fn new_ok_string_with_capacity(cap: usize) -> Self {
+        Self::new_ok(String::with_capacity(cap))
+    }
+}
+impl<U, E: Default> Result<Box<U>, E> {
+    /// Returns the box as an owning raw pointer and `E::default()` on `Ok`,
+    /// or a null pointer and the error on `Err`. Reclaim the pointer with [`Box::from_raw`].
+    pub fn into_nullable_ptr(self) -> (*mut U, E) {
+        match self.into_result() {
+            Ok(ok) => (Box::into_raw(ok), E::default()),
+            Err(err) => (std::ptr::null_mut(), err),
+        }
+    }
+}
+impl<T, E: std::error::Error> Result<T, E> {
+    /// Iterates over the error and its [`source`](std::error::Error::source) chain.
+    /// Empty for `Ok`.
+    pub fn err_sources(&self) -> impl Iterator<Item = &(dyn std::error::Error + '_)> {
+        std::iter::successors(
+            self.as_ref().err().map(|err| err as &dyn std::error::Error),
+            |err| err.source(),
+        )
+    }
+}
+impl<'a, T, E> Result<&'a T, E> {
+    /// Borrowed `Ok`, e.g. for building `Result<&T, &E>` views like [`Result::as_ref`].
+    pub const fn new_ok_ref(t: &'a T) -> Self {
+        Self::new_ok(t)
+    }
+}
+impl<'a, T, E> Result<T, &'a E> {
+    /// Borrowed `Err`, e.g. for building `Result<&T, &E>` views like [`Result::as_ref`].
+    pub const fn new_err_ref(e: &'a E) -> Self {
+        Self::new_err(e)
+    }
+}
 
 impl<T, E> From<Result<T, E>> for core::result::Result<T, E> {
     fn from(val: Result<T, E>) -> core::result::Result<T, E> {
@@ -182,14 +881,21 @@ impl<T, E> From<core::result::Result<T, E>> for Result<T, E> {
         Self::from_result(val)
     }
 }
+/// Discards the error, same as [`Result::ok`]. Use [`Result::err`] for the other side.
+impl<T, E> From<Result<T, E>> for Option<T> {
+    fn from(val: Result<T, E>) -> Option<T> {
+        val.ok()
+    }
+}
 
 impl<T: Debug, E: Debug> Debug for Result<T, E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = self.checked_kind();
         let mut structt = f.debug_struct("Result");
-        let dbg = structt.field("kind", &self.kind);
+        let dbg = structt.field("kind", &kind);
 
         unsafe {
-            match self.kind {
+            match kind {
                 ResultKind::Ok => dbg.field("data", self.data.ok.deref()).finish(),
                 ResultKind::Err => dbg.field("data", self.data.err.deref()).finish(),
             }
@@ -199,10 +905,13 @@ impl<T: Debug, E: Debug> Debug for Result<T, E> {
 
 impl<T: Clone, E: Clone> Clone for Result<T, E> {
     fn clone(&self) -> Self {
+        let kind = self.checked_kind();
         unsafe {
             Self {
-                kind: self.kind,
-                data: match self.kind {
+                kind,
+                #[cfg(feature = "checked")]
+                check: self.check,
+                data: match kind {
                     ResultKind::Ok => ResultData {
                         ok: self.data.ok.clone(),
                     },
@@ -228,6 +937,12 @@ impl<T: PartialEq, E: PartialEq> PartialEq for Result<T, E> {
 
 impl<T, E> Drop for Result<T, E> {
     fn drop(&mut self) {
+        #[cfg(feature = "checked")]
+        if !self.is_tag_consistent() {
+            // Dropping the wrong variant is UB, leak the payload instead.
+            return;
+        }
+
         unsafe {
             match self.kind {
                 ResultKind::Ok => {
@@ -241,15 +956,1996 @@ impl<T, E> Drop for Result<T, E> {
     }
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum ResultKind {
-    Ok,
-    Err,
+/// Calls `f` up to `attempts` times, returning the first `Ok` or the last `Err`.
+///
+/// Intermediate errors are dropped.
+///
+/// # Panics
+/// If `attempts` is 0.
+pub fn retry<T, E>(attempts: usize, mut f: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    assert!(attempts != 0, "attempts must be non-zero");
+    let mut last = f();
+    for _ in 1..attempts {
+        if last.is_ok() {
+            break;
+        }
+        last = f();
+    }
+    last
 }
 
-#[repr(C)]
-pub union ResultData<T, E> {
-    pub ok: ManuallyDrop<T>,
-    pub err: ManuallyDrop<E>,
+/// Drains `iter` into its `Ok` and `Err` payloads, preserving order within each side.
+pub fn partition_results<T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> (Vec<T>, Vec<E>) {
+    let mut oks = Vec::new();
+    let mut errs = Vec::new();
+    for result in iter {
+        match result.into_result() {
+            Ok(ok) => oks.push(ok),
+            Err(err) => errs.push(err),
+        }
+    }
+    (oks, errs)
+}
+
+/// Returns the first error in `iter`, dropping the `Ok` values before it.
+///
+/// Stops consuming `iter` at the first error.
+pub fn first_err<T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> Option<E> {
+    iter.into_iter().find_map(Result::err)
+}
+
+/// Yields the `Ok` values of `iter` up to the first error, which is dropped and ends the iterator.
+pub fn map_while_ok<T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> impl Iterator<Item = T> {
+    iter.into_iter().map_while(Result::ok)
+}
+
+/// Collects the `Ok` values of `iter`, or returns the index and payload of the first error.
+pub fn collect_indexed_errs<T, E>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+) -> core::result::Result<Vec<T>, (usize, E)> {
+    iter.into_iter()
+        .enumerate()
+        .map(|(index, result)| result.into_result().map_err(|err| (index, err)))
+        .collect()
+}
+
+/// Groups the `Ok` values of `iter` into chunks of `chunk` elements, the last one
+/// possibly shorter. Stops at the first error and returns it.
+///
+/// # Panics
+/// If `chunk` is 0.
+pub fn collect_chunked<T, E>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+    chunk: usize,
+) -> core::result::Result<Vec<Vec<T>>, E> {
+    assert!(chunk != 0, "chunk size must be non-zero");
+    let mut chunks = Vec::new();
+    let mut current = Vec::with_capacity(chunk);
+    for result in iter {
+        current.push(result.into_result()?);
+        if current.len() == chunk {
+            chunks.push(std::mem::replace(&mut current, Vec::with_capacity(chunk)));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    Ok(chunks)
+}
+
+/// Wraps a poller returning core results into a future resolving to [`Result`].
+pub fn poll_fn_ffi<T, E>(
+    mut f: impl FnMut(&mut Context<'_>) -> Poll<core::result::Result<T, E>>,
+) -> impl Future<Output = Result<T, E>> {
+    std::future::poll_fn(move |cx| f(cx).map(Result::from_result))
+}
+
+/// Applies `f` to every `Ok` payload in place, skipping errors.
+pub fn apply_all<T, E>(results: &mut [Result<T, E>], f: impl Fn(&mut T)) {
+    results.iter_mut().filter_map(Result::ok_mut).for_each(f);
+}
+
+/// Collects the leading `Ok` values of `iter` and returns them with the first error, if any.
+///
+/// Nothing past the error is pulled from `iter`, so pass `&mut iter` to keep the remaining items.
+pub fn take_ok_until_err<T, E>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+) -> (Vec<T>, Option<E>) {
+    let mut oks = Vec::new();
+    for result in iter {
+        match result.into_result() {
+            Ok(ok) => oks.push(ok),
+            Err(err) => return (oks, Some(err)),
+        }
+    }
+    (oks, None)
+}
+
+/// Clones borrowed `Ok` values into a vector, or clones the first borrowed error.
+pub fn collect_cloned<'a, T: Clone + 'a, E: Clone + 'a>(
+    iter: impl IntoIterator<Item = Result<&'a T, &'a E>>,
+) -> core::result::Result<Vec<T>, E> {
+    iter.into_iter()
+        .map(|result| result.into_result().cloned().map_err(Clone::clone))
+        .collect()
+}
+
+/// Calls `f` on each item until it fails. The remaining items are dropped unprocessed.
+pub fn try_for_each_ffi<T, E>(
+    iter: impl IntoIterator<Item = T>,
+    mut f: impl FnMut(T) -> Result<(), E>,
+) -> Result<(), E> {
+    iter.into_iter()
+        .try_for_each(|item| f(item).into_result())
+        .into()
+}
+
+/// Collects the errors of `iter`, skipping an error equal to the previously collected one.
+/// `Ok` values are dropped and do not break a run of equal errors.
+pub fn collect_unique_errs<T, E: PartialEq>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+) -> Vec<E> {
+    let mut errs: Vec<E> = Vec::new();
+    for err in iter.into_iter().filter_map(Result::err) {
+        if errs.last() != Some(&err) {
+            errs.push(err);
+        }
+    }
+    errs
+}
+
+/// Folds the `Ok` values of `iter` into `init` with `f`, stopping at the first
+/// error from either `iter` or `f`. The remaining items are dropped unprocessed.
+pub fn scan_ok<T, E, S>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+    init: S,
+    mut f: impl FnMut(&mut S, T) -> Result<(), E>,
+) -> Result<S, E> {
+    let mut state = init;
+    for result in iter {
+        let ok = crate::ffi_try!(result);
+        crate::ffi_try!(f(&mut state, ok));
+    }
+    Result::new_ok(state)
+}
+
+/// Collects all `Ok` values if `iter` has no errors, otherwise all errors,
+/// dropping the `Ok` values. Unlike short-circuiting collection, all items are consumed.
+pub fn merge_all<T, E>(
+    iter: impl IntoIterator<Item = Result<T, E>>,
+) -> core::result::Result<Vec<T>, Vec<E>> {
+    let mut oks = Vec::new();
+    let mut errs = Vec::new();
+    for result in iter {
+        match result.into_result() {
+            Ok(ok) if errs.is_empty() => oks.push(ok),
+            Ok(_) => {}
+            Err(err) => {
+                oks.clear();
+                errs.push(err);
+            }
+        }
+    }
+    if errs.is_empty() { Ok(oks) } else { Err(errs) }
+}
+
+/// Removes the errors from `v` and returns them in order, keeping the `Ok` entries in place.
+pub fn drain_errs<T, E>(v: &mut Vec<Result<T, E>>) -> Vec<E> {
+    let (oks, errs): (Vec<_>, Vec<_>) = std::mem::take(v).into_iter().partition(Result::is_ok);
+    *v = oks;
+    errs.into_iter().filter_map(Result::err).collect()
+}
+
+/// Counts the `Ok` items of `iter`, dropping every payload.
+pub fn count_ok<T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> usize {
+    iter.into_iter().filter(Result::is_ok).count()
+}
+/// Counts the `Err` items of `iter`, dropping every payload.
+pub fn count_err<T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> usize {
+    iter.into_iter().filter(Result::is_err).count()
+}
+
+/// Sanity check of the ABI and ownership handling of [`Result`] on the current
+/// platform, meant to be called from downstream test suites.
+///
+/// Verifies for a few monomorphizations that the tag lives at offset 0, that the
+/// data follows it at the payload alignment and fits the larger payload, that
+/// the tag values are 0 and 1, and that round-trips through the core result,
+/// [`Result::into_raw`] and [`Result::into_c_tagged`] drop every payload exactly once.
+pub fn self_check() -> bool {
+    fn layout_ok<T, E>() -> bool {
+        std::mem::offset_of!(Result<T, E>, kind) == 0
+            && std::mem::offset_of!(Result<T, E>, data) == std::mem::align_of::<ResultData<T, E>>()
+            && std::mem::size_of::<ResultData<T, E>>() >= Result::<T, E>::max_payload_size()
+    }
+
+    let layouts = layout_ok::<u8, u8>()
+        && layout_ok::<u64, u8>()
+        && layout_ok::<(), String>()
+        && layout_ok::<[u8; 3], u128>()
+        && layout_ok::<Box<u8>, ()>();
+    let tags = Result::<(), ()>::new_ok(()).discriminant() == 0
+        && Result::<(), ()>::new_err(()).discriminant() == 1;
+
+    let drops = Rc::new(Cell::new(0));
+    let roundtrips = {
+        let ok: Result<Counted, Counted> = Result::new_ok(Counted(drops.clone()));
+        let err: Result<Counted, Counted> = Result::new_err(Counted(drops.clone()));
+
+        let ok = Result::from_result(ok.into_result());
+        let err = unsafe { Result::from_raw(err.into_raw()) };
+        let (kind, t, e) = ok.into_c_tagged();
+        let ok = unsafe { Result::from_c_tagged(kind, t, e) };
+
+        ok.is_ok() && err.is_err() && drops.get() == 0
+    };
+
+    layouts && tags && roundtrips && drops.get() == 2
+}
+
+/// Counts its own drops, to check that payloads are dropped exactly once.
+#[derive(Debug)]
+struct Counted(Rc<Cell<usize>>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// Index of the first error in `results`, or `None` if all are `Ok`.
+pub fn first_err_index<T, E>(results: &[Result<T, E>]) -> Option<usize> {
+    results.iter().position(Result::is_err)
+}
+
+/// Collections whose spare capacity can be released, see [`Result::shrink_ok`].
+pub trait ShrinkToFit {
+    fn shrink_to_fit(&mut self);
+}
+
+impl<U> ShrinkToFit for Vec<U> {
+    fn shrink_to_fit(&mut self) {
+        Vec::shrink_to_fit(self)
+    }
+}
+impl ShrinkToFit for String {
+    fn shrink_to_fit(&mut self) {
+        String::shrink_to_fit(self)
+    }
+}
+
+/// Callbacks for [`Result::accept`].
+pub trait Visitor<T, E> {
+    fn on_ok(&mut self, ok: &T);
+    fn on_err(&mut self, err: &E);
+}
+
+fn display_to_cstring(val: &impl Display) -> CString {
+    let text = val.to_string().replace('\0', "\u{FFFD}");
+    CString::new(text).expect("interior NUL bytes were replaced")
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResultKind {
+    Ok,
+    Err,
+}
+
+impl ResultKind {
+    /// Validates a raw tag byte, e.g. one read from untrusted memory.
+    pub const fn from_tag(tag: u8) -> core::result::Result<Self, InvalidTag> {
+        match tag {
+            0 => Ok(ResultKind::Ok),
+            1 => Ok(ResultKind::Err),
+            _ => Err(InvalidTag(tag)),
+        }
+    }
+}
+
+impl TryFrom<u8> for ResultKind {
+    type Error = InvalidTag;
+
+    fn try_from(tag: u8) -> core::result::Result<Self, InvalidTag> {
+        Self::from_tag(tag)
+    }
+}
+
+/// A tag byte that is neither [`ResultKind::Ok`] nor [`ResultKind::Err`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidTag(pub u8);
+
+impl Display for InvalidTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid result tag: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTag {}
+
+/// The tag of a [`Result`] does not match its redundant copy, see [`Result::try_into_result`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorruptTag;
+
+impl Display for CorruptTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "result tag is inconsistent with its data")
+    }
+}
+
+impl std::error::Error for CorruptTag {}
+
+#[repr(C)]
+pub union ResultData<T, E> {
+    pub ok: ManuallyDrop<T>,
+    pub err: ManuallyDrop<E>,
+}
+
+/// Heap-allocated [`ResultData`] with its tag, see [`Result::into_raw`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawResult<T, E> {
+    pub kind: ResultKind,
+    pub data: *mut ResultData<T, E>,
+}
+
+impl<T, E> ResultData<T, E> {
+    /// # Safety
+    /// The `ok` field must be the active one.
+    pub unsafe fn ok_ref(&self) -> &T {
+        unsafe { self.ok.deref() }
+    }
+
+    /// # Safety
+    /// The `err` field must be the active one.
+    pub unsafe fn err_ref(&self) -> &E {
+        unsafe { self.err.deref() }
+    }
+}
+
+/// Native enum counterpart of [`Result`].
+///
+/// `#[repr(C, u8)]` lays the enum out as a `u8` tag followed by a `#[repr(C)]`
+/// union of the payloads, which matches the layout of [`Result`] unless the
+/// `checked` feature appends its redundant tag. Unlike [`Result`] it can be
+/// matched on directly and needs no `unsafe`, while [`Result`] stays the type
+/// whose fields C code can name. In both types the unused tag values act as a
+/// niche, so wrapping either in `Option` does not make it larger.
+#[repr(C, u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResultEnum<T, E> {
+    Ok(T),
+    Err(E),
+}
+
+impl<T, E> From<Result<T, E>> for ResultEnum<T, E> {
+    fn from(val: Result<T, E>) -> Self {
+        match val.into_result() {
+            Ok(ok) => ResultEnum::Ok(ok),
+            Err(err) => ResultEnum::Err(err),
+        }
+    }
+}
+impl<T, E> From<ResultEnum<T, E>> for Result<T, E> {
+    fn from(val: ResultEnum<T, E>) -> Self {
+        match val {
+            ResultEnum::Ok(ok) => Self::new_ok(ok),
+            ResultEnum::Err(err) => Self::new_err(err),
+        }
+    }
+}
+
+/// Non-union alternative to [`Result`] for C APIs that expect both fields materialized.
+///
+/// Only the field selected by `is_ok` is meaningful. Converting from [`Result`]
+/// fills the inactive field with its `Default`, hence the `T: Default, E: Default`
+/// bounds; the struct is also always as large as `T` and `E` combined.
+#[repr(C)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WideResult<T, E> {
+    pub is_ok: bool,
+    pub value: T,
+    pub error: E,
+}
+
+impl<T: Default, E: Default> From<Result<T, E>> for WideResult<T, E> {
+    fn from(val: Result<T, E>) -> Self {
+        match val.into_result() {
+            Ok(value) => Self {
+                is_ok: true,
+                value,
+                error: E::default(),
+            },
+            Err(error) => Self {
+                is_ok: false,
+                value: T::default(),
+                error,
+            },
+        }
+    }
+}
+impl<T, E> From<WideResult<T, E>> for Result<T, E> {
+    fn from(val: WideResult<T, E>) -> Self {
+        if val.is_ok {
+            Self::new_ok(val.value)
+        } else {
+            Self::new_err(val.error)
+        }
+    }
+}
+
+/// Hashes and compares a [`Result`] by its `Ok` payload only.
+///
+/// All errors hash to the same value and compare equal to each other, so as
+/// map keys every `Err` collapses into a single entry.
+pub struct HashByOk<'a, T, E>(pub &'a Result<T, E>);
+
+impl<T: Hash, E> Hash for HashByOk<'_, T, E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.kind.hash(state);
+        if let Some(ok) = self.0.as_ref().ok() {
+            ok.hash(state);
+        }
+    }
+}
+impl<T: PartialEq, E> PartialEq for HashByOk<'_, T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_err(other.0)
+    }
+}
+impl<T: Eq, E> Eq for HashByOk<'_, T, E> {}
+
+impl<T, E> Clone for HashByOk<'_, T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T, E> Copy for HashByOk<'_, T, E> {}
+
+/// Lazily computed [`Result`], for caching an expensive fallible computation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultCache<T, E> {
+    value: Option<Result<T, E>>,
+}
+
+impl<T, E> ResultCache<T, E> {
+    pub const fn new() -> Self {
+        Self { value: None }
+    }
+
+    /// Returns the cached result, computing it with `f` on the first call.
+    pub fn get_or_init(&mut self, f: impl FnOnce() -> Result<T, E>) -> &Result<T, E> {
+        self.value.get_or_insert_with(f)
+    }
+
+    pub fn get(&self) -> Option<&Result<T, E>> {
+        self.value.as_ref()
+    }
+
+    pub fn take(&mut self) -> Option<Result<T, E>> {
+        self.value.take()
+    }
+}
+
+impl<T, E> Default for ResultCache<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `Result::new_err(err)` from the enclosing function if `cond` is false.
+#[macro_export]
+macro_rules! ffi_ensure {
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            return $crate::Result::new_err($err);
+        }
+    };
+}
+
+/// `?` for [`Result`] on stable: evaluates to the `Ok` value, or returns the
+/// error from the enclosing function, converted with [`Into`].
+#[macro_export]
+macro_rules! ffi_try {
+    ($expr:expr $(,)?) => {
+        match $crate::Result::into_result($expr) {
+            ::core::result::Result::Ok(val) => val,
+            ::core::result::Result::Err(err) => {
+                return $crate::Result::new_err(::core::convert::Into::into(err));
+            }
+        }
+    };
+}
+
+/// Asserts that a [`Result`] is `Ok` and evaluates to the `Ok` value.
+#[macro_export]
+macro_rules! assert_ok {
+    ($expr:expr $(,)?) => {
+        match $crate::Result::into_result($expr) {
+            ::core::result::Result::Ok(val) => val,
+            ::core::result::Result::Err(err) => {
+                ::core::panic!("assertion failed: expected Ok, got Err({:?})", err)
+            }
+        }
+    };
+}
+
+/// Asserts that a [`Result`] is `Err` and evaluates to the error.
+#[macro_export]
+macro_rules! assert_err {
+    ($expr:expr $(,)?) => {
+        match $crate::Result::into_result($expr) {
+            ::core::result::Result::Err(err) => err,
+            ::core::result::Result::Ok(val) => {
+                ::core::panic!("assertion failed: expected Err, got Ok({:?})", val)
+            }
+        }
+    };
+}
+
+/// Checks that a [`Result`] (or a reference to one) is `Ok`, without consuming it.
+#[macro_export]
+macro_rules! is_ffi_ok {
+    ($expr:expr $(,)?) => {
+        *($expr).kind() == $crate::ResultKind::Ok
+    };
+}
+
+/// Checks that a [`Result`] (or a reference to one) is `Err`, without consuming it.
+#[macro_export]
+macro_rules! is_ffi_err {
+    ($expr:expr $(,)?) => {
+        *($expr).kind() == $crate::ResultKind::Err
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+
+    #[test]
+    fn from_tag_rejects_invalid_bytes() {
+        assert_eq!(ResultKind::from_tag(0), Ok(ResultKind::Ok));
+        assert_eq!(ResultKind::from_tag(1), Ok(ResultKind::Err));
+        assert_eq!(ResultKind::from_tag(7), Err(InvalidTag(7)));
+        assert_eq!(ResultKind::try_from(255u8), Err(InvalidTag(255)));
+    }
+
+    fn panic_location(f: impl FnOnce() + std::panic::UnwindSafe) -> (String, u32) {
+        thread_local! {
+            static LOCATION: std::cell::RefCell<Option<(String, u32)>> = const { std::cell::RefCell::new(None) };
+        }
+        let prev_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|info| {
+            let loc = info
+                .location()
+                .map(|loc| (loc.file().to_string(), loc.line()));
+            LOCATION.with(|cell| *cell.borrow_mut() = loc);
+        }));
+        let res = std::panic::catch_unwind(f);
+        std::panic::set_hook(prev_hook);
+        assert!(res.is_err());
+        LOCATION.with(|cell| cell.borrow_mut().take()).unwrap()
+    }
+
+    #[test]
+    fn unwrap_and_expect_report_caller_location() {
+        let ok = || Result::<u8, &str>::new_ok(1);
+        let err = || Result::<u8, &str>::new_err("e");
+
+        let (loc, line) = (panic_location(|| _ = err().unwrap()), line!());
+        assert_eq!(loc, (file!().to_string(), line));
+        let (loc, line) = (panic_location(|| _ = err().expect("msg")), line!());
+        assert_eq!(loc, (file!().to_string(), line));
+        let (loc, line) = (panic_location(|| _ = ok().unwrap_err()), line!());
+        assert_eq!(loc, (file!().to_string(), line));
+        let (loc, line) = (panic_location(|| _ = ok().expect_err("msg")), line!());
+        assert_eq!(loc, (file!().to_string(), line));
+    }
+
+    #[test]
+    fn retry_stops_at_first_ok() {
+        let mut calls = 0;
+        let res = retry(5, || {
+            calls += 1;
+            if calls == 3 {
+                Result::new_ok(calls)
+            } else {
+                Result::new_err(calls)
+            }
+        });
+        assert_eq!(res, Result::new_ok(3));
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let res: Result<(), i32> = retry(4, || {
+            calls += 1;
+            Result::new_err(calls)
+        });
+        assert_eq!(res, Result::new_err(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempts must be non-zero")]
+    fn retry_rejects_zero_attempts() {
+        let _ = retry(0, || Result::<(), ()>::new_ok(()));
+    }
+
+    #[test]
+    fn first_err_stops_and_drops_prior_oks_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mut pulled = 0;
+        let items = [
+            Result::new_ok(Counted(drops.clone())),
+            Result::new_ok(Counted(drops.clone())),
+            Result::new_err(7),
+            Result::new_err(8),
+        ];
+        let iter = items.into_iter().inspect(|_| pulled += 1);
+
+        assert_eq!(first_err(iter), Some(7));
+        assert_eq!(pulled, 3);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn c_tagged_round_trip() {
+        let drops = Rc::new(Cell::new(0));
+
+        let res: Result<Counted, String> = Result::new_ok(Counted(drops.clone()));
+        let (kind, ok, err) = res.into_c_tagged();
+        assert_eq!(kind, ResultKind::Ok);
+        assert!(!ok.is_null() && err.is_null());
+        let res = unsafe { Result::from_c_tagged(kind, ok, err) };
+        assert!(res.is_ok());
+        assert_eq!(drops.get(), 0);
+        drop(res);
+        assert_eq!(drops.get(), 1);
+
+        let res: Result<Counted, String> = Result::new_err("boom".to_string());
+        let (kind, ok, err) = res.into_c_tagged();
+        assert_eq!(kind, ResultKind::Err);
+        assert!(ok.is_null() && !err.is_null());
+        let res = unsafe { Result::from_c_tagged(kind, ok, err) };
+        assert_eq!(res.err().as_deref(), Some("boom"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "checked"))]
+    fn result_enum_matches_result_layout() {
+        fn check<T, E>(ok: T, err: E) {
+            use std::mem::{align_of, size_of};
+            assert_eq!(size_of::<ResultEnum<T, E>>(), size_of::<Result<T, E>>());
+            assert_eq!(align_of::<ResultEnum<T, E>>(), align_of::<Result<T, E>>());
+            assert_eq!(std::mem::offset_of!(Result<T, E>, kind), 0);
+
+            let first_byte =
+                |e: &ResultEnum<T, E>| unsafe { *(e as *const ResultEnum<T, E>).cast::<u8>() };
+            assert_eq!(first_byte(&ResultEnum::Ok(ok)), ResultKind::Ok as u8);
+            assert_eq!(first_byte(&ResultEnum::Err(err)), ResultKind::Err as u8);
+        }
+        check(1u8, 2u8);
+        check(1u64, 2u8);
+        check((), [3u8; 5]);
+        check(1u16, 2u128);
+
+        use std::mem::size_of;
+        assert_eq!(
+            size_of::<Option<ResultEnum<u32, u32>>>(),
+            size_of::<ResultEnum<u32, u32>>()
+        );
+        assert_eq!(
+            size_of::<Option<Result<u32, u32>>>(),
+            size_of::<Result<u32, u32>>()
+        );
+    }
+
+    #[test]
+    fn result_enum_round_trip() {
+        let res: Result<u32, String> = Result::new_ok(5);
+        let e = ResultEnum::from(res);
+        assert_eq!(e, ResultEnum::Ok(5));
+        assert_eq!(Result::from(e), Result::new_ok(5));
+
+        let res: Result<u32, String> = Result::new_err("e".to_string());
+        let e = ResultEnum::from(res);
+        assert_eq!(e, ResultEnum::Err("e".to_string()));
+        assert_eq!(Result::from(e), Result::new_err("e".to_string()));
+    }
+
+    #[test]
+    fn set_ok_and_set_err_drop_previous_payload() {
+        let drops = Rc::new(Cell::new(0));
+
+        let res: Result<Counted, Counted> = Result::new_ok(Counted(drops.clone()));
+        let res = res.set_err(Counted(drops.clone()));
+        assert!(res.is_err());
+        assert_eq!(drops.get(), 1);
+
+        let res = res.set_ok(Counted(drops.clone()));
+        assert!(res.is_ok());
+        assert_eq!(drops.get(), 2);
+
+        drop(res);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn coalesce_prefers_first_ok_and_drops_the_other() {
+        let ok_drops = Rc::new(Cell::new(0));
+        let err_drops = Rc::new(Cell::new(0));
+        let ok =
+            |n: u8| Result::<(u8, Counted), (u8, Counted)>::new_ok((n, Counted(ok_drops.clone())));
+        let err = |n: u8| {
+            Result::<(u8, Counted), (u8, Counted)>::new_err((n, Counted(err_drops.clone())))
+        };
+
+        let res = ok(1).coalesce(ok(2));
+        assert_eq!(res.as_ref().ok().map(|v| v.0), Some(1));
+        assert_eq!(ok_drops.get(), 1);
+
+        let res = ok(1).coalesce(err(2));
+        assert_eq!(res.as_ref().ok().map(|v| v.0), Some(1));
+        assert_eq!(err_drops.get(), 1);
+
+        let res = err(1).coalesce(ok(2));
+        assert_eq!(res.as_ref().ok().map(|v| v.0), Some(2));
+        assert_eq!(err_drops.get(), 2);
+
+        let res = err(1).coalesce(err(2));
+        assert_eq!(res.as_ref().err().map(|v| v.0), Some(1));
+        assert_eq!(err_drops.get(), 3);
+        assert_eq!(ok_drops.get(), 1);
+    }
+
+    #[test]
+    fn raw_union_round_trip() {
+        let drops = Rc::new(Cell::new(0));
+
+        let res: Result<Counted, String> = Result::new_ok(Counted(drops.clone()));
+        let raw = res.into_raw();
+        assert_eq!(raw.kind, ResultKind::Ok);
+        let res = unsafe { Result::from_raw(raw) };
+        assert!(res.is_ok());
+        assert_eq!(drops.get(), 0);
+        drop(res);
+        assert_eq!(drops.get(), 1);
+
+        let res: Result<Counted, String> = Result::new_err("boom".to_string());
+        let raw = res.into_raw();
+        assert_eq!(raw.kind, ResultKind::Err);
+        let res = unsafe { Result::from_raw(raw) };
+        assert_eq!(res.err().as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn as_pin_mut_polls_pinned_ok_future() {
+        let mut yielded = false;
+        let yield_once = std::future::poll_fn(move |cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        });
+        let fut = async move {
+            yield_once.await;
+            5
+        };
+
+        let mut res: Pin<&mut Result<_, ()>> = std::pin::pin!(Result::new_ok(fut));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+
+        let mut poll = || res.as_mut().as_pin_mut().unwrap().poll(&mut cx);
+        assert_eq!(poll(), Poll::Pending);
+        assert_eq!(poll(), Poll::Ready(5));
+
+        let res: Pin<&mut Result<(), u8>> = std::pin::pin!(Result::new_err(3));
+        assert_eq!(res.as_ref().as_pin_ref().err().map(|err| *err), Some(3));
+    }
+
+    #[test]
+    fn transmute_err_to_transparent_newtype() {
+        #[repr(transparent)]
+        #[derive(Debug, PartialEq)]
+        struct Code(i32);
+        #[repr(transparent)]
+        #[derive(Debug, PartialEq)]
+        struct Message(String);
+
+        let res: Result<u8, i32> = Result::new_err(-4);
+        assert_eq!(
+            unsafe { res.transmute_err::<Code>() },
+            Result::new_err(Code(-4))
+        );
+        let res: Result<u8, i32> = Result::new_ok(9);
+        assert_eq!(unsafe { res.transmute_err::<Code>() }, Result::new_ok(9));
+
+        let res: Result<u8, String> = Result::new_err("owned".to_string());
+        let res = unsafe { res.transmute_err::<Message>() };
+        assert_eq!(res, Result::new_err(Message("owned".to_string())));
+    }
+
+    #[test]
+    fn drop_contents_drops_heap_placed_payload_once() {
+        let drops = Rc::new(Cell::new(0));
+        let ptr = Box::into_raw(Box::new(Result::<Counted, String>::new_ok(Counted(
+            drops.clone(),
+        ))));
+
+        unsafe { Result::drop_contents(ptr) };
+        assert_eq!(drops.get(), 1);
+
+        // Free the memory without dropping the result a second time.
+        drop(unsafe { Box::from_raw(ptr.cast::<ManuallyDrop<Result<Counted, String>>>()) });
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn swap_with_exchanges_variants_without_dropping() {
+        let drops = Rc::new(Cell::new(0));
+        let mut a: Result<Counted, String> = Result::new_ok(Counted(drops.clone()));
+        let mut b: Result<Counted, String> = Result::new_err("e".to_string());
+
+        a.swap_with(&mut b);
+        assert_eq!(a.as_ref().err().map(String::as_str), Some("e"));
+        assert!(b.is_ok());
+        assert_eq!(drops.get(), 0);
+
+        drop(a);
+        drop(b);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn unit_results_behave_like_core() {
+        let ok: Result<(), String> = Result::new_ok(());
+        let err: Result<(), String> = Result::new_err("e".to_string());
+
+        assert_eq!(ok.clone().into_result(), Ok(()));
+        assert_eq!(err.clone().into_result(), Err("e".to_string()));
+        assert_eq!(ok.clone().ok(), Some(()));
+        assert_eq!(err.clone().ok(), None);
+        assert_eq!(ok.map(|()| 1), Result::new_ok(1));
+        assert_eq!(err.map(|()| 1), Result::new_err("e".to_string()));
+    }
+
+    #[test]
+    fn try_for_each_ffi_stops_at_first_error_and_drops_rest() {
+        let drops = Rc::new(Cell::new(0));
+        let items: Vec<Counted> = (0..5).map(|_| Counted(drops.clone())).collect();
+        let mut calls = 0;
+
+        let res = try_for_each_ffi(items, |item| {
+            calls += 1;
+            drop(item);
+            if calls == 3 {
+                Result::new_err(calls)
+            } else {
+                Result::new_ok(())
+            }
+        });
+        assert_eq!(res, Result::new_err(3));
+        assert_eq!(calls, 3);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn merge_all_collects_everything_and_drops_oks_on_error() {
+        let res = merge_all([Result::<u8, u8>::new_ok(1), Result::new_ok(2)]);
+        assert_eq!(res, Ok(vec![1, 2]));
+
+        let drops = Rc::new(Cell::new(0));
+        let items = [
+            Result::new_ok(Counted(drops.clone())),
+            Result::new_err(1),
+            Result::new_ok(Counted(drops.clone())),
+            Result::new_err(2),
+        ];
+        assert_eq!(merge_all(items).err(), Some(vec![1, 2]));
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn drain_errs_keeps_oks_in_place() {
+        let mut v = vec![
+            Result::new_ok(1),
+            Result::new_err("a"),
+            Result::new_ok(2),
+            Result::new_err("b"),
+        ];
+        assert_eq!(drain_errs(&mut v), vec!["a", "b"]);
+        assert_eq!(v, vec![Result::new_ok(1), Result::new_ok(2)]);
+    }
+
+    #[test]
+    fn count_ok_and_err_drop_each_payload_once() {
+        let drops = Rc::new(Cell::new(0));
+        let mixed = || {
+            vec![
+                Result::<Counted, Counted>::new_ok(Counted(drops.clone())),
+                Result::new_err(Counted(drops.clone())),
+                Result::new_ok(Counted(drops.clone())),
+            ]
+        };
+        assert_eq!(count_ok(mixed()), 2);
+        assert_eq!(drops.get(), 3);
+        assert_eq!(count_err(mixed()), 1);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn option_from_keeps_ok_and_discards_err() {
+        assert_eq!(Option::from(Result::<u8, String>::new_ok(3)), Some(3));
+        assert_eq!(Option::<u8>::from(Result::new_err("e".to_string())), None);
+    }
+
+    #[test]
+    fn wide_result_round_trip_defaults_inactive_field() {
+        let wide = WideResult::from(Result::<u32, String>::new_ok(7));
+        assert_eq!(
+            wide,
+            WideResult {
+                is_ok: true,
+                value: 7,
+                error: String::new(),
+            }
+        );
+        assert_eq!(Result::from(wide), Result::new_ok(7));
+
+        let wide = WideResult::from(Result::<u32, String>::new_err("e".to_string()));
+        assert_eq!(
+            wide,
+            WideResult {
+                is_ok: false,
+                value: 0,
+                error: "e".to_string(),
+            }
+        );
+        assert_eq!(Result::from(wide), Result::new_err("e".to_string()));
+    }
+
+    fn hundred_div(n: u32) -> Result<u32, String> {
+        ffi_ensure!(n != 0, "division by zero".to_string());
+        Result::new_ok(100 / n)
+    }
+
+    #[test]
+    fn ffi_ensure_returns_err_on_false_condition() {
+        assert_eq!(hundred_div(4), Result::new_ok(25));
+        assert_eq!(
+            hundred_div(0),
+            Result::new_err("division by zero".to_string())
+        );
+    }
+
+    fn widened_sum(a: Result<u32, u8>, b: Result<u32, u8>) -> Result<u32, u32> {
+        let a = ffi_try!(a);
+        let b = ffi_try!(b);
+        Result::new_ok(a + b)
+    }
+
+    #[test]
+    fn ffi_try_unwraps_ok_and_converts_early_err() {
+        assert_eq!(
+            widened_sum(Result::new_ok(1), Result::new_ok(2)),
+            Result::new_ok(3)
+        );
+        assert_eq!(
+            widened_sum(Result::new_err(4), Result::new_ok(2)),
+            Result::new_err(4u32)
+        );
+        assert_eq!(
+            widened_sum(Result::new_ok(1), Result::new_err(5)),
+            Result::new_err(5u32)
+        );
+    }
+
+    #[test]
+    fn capture_backtrace_only_on_err() {
+        let res: Result<u8, &str> = Result::new_ok(1);
+        assert_eq!(res.capture_backtrace().ok(), Some(1));
+
+        let res: Result<u8, &str> = Result::new_err("e");
+        let (_backtrace, err) = res.capture_backtrace().unwrap_err();
+        assert_eq!(err, "e");
+    }
+
+    #[test]
+    fn partition_results_keeps_order_on_each_side() {
+        let items = [
+            Result::new_ok(1),
+            Result::new_err("a"),
+            Result::new_ok(2),
+            Result::new_ok(3),
+            Result::new_err("b"),
+        ];
+        assert_eq!(partition_results(items), (vec![1, 2, 3], vec!["a", "b"]));
+    }
+
+    #[test]
+    fn result_data_reads_active_field() {
+        let res: Result<u8, String> = Result::new_ok(4);
+        assert_eq!(unsafe { res.data().ok_ref() }, &4);
+
+        let res: Result<u8, String> = Result::new_err("e".to_string());
+        assert_eq!(unsafe { res.data().err_ref() }, "e");
+    }
+
+    #[test]
+    fn map_ref_borrows_ok_only() {
+        let res: Result<String, u8> = Result::new_ok("abc".to_string());
+        assert_eq!(res.map_ref(String::len), Some(3));
+        assert!(res.is_ok());
+
+        let res: Result<String, u8> = Result::new_err(1);
+        assert_eq!(res.map_ref(String::len), None);
+    }
+
+    #[test]
+    fn shrink_ok_releases_spare_capacity() {
+        let mut res: Result<Vec<u8>, String> = Result::new_ok(Vec::with_capacity(64));
+        res.ok_mut().unwrap().push(1);
+        res.shrink_ok();
+        let ok = res.as_ref().ok().unwrap();
+        assert_eq!(ok, &[1]);
+        assert!(ok.capacity() < 64);
+
+        let mut res: Result<Vec<u8>, String> = Result::new_err("e".to_string());
+        res.shrink_ok();
+        assert_eq!(res, Result::new_err("e".to_string()));
+    }
+
+    #[test]
+    fn as_ok_slice_views_ok_vec() {
+        let res: Result<Vec<u8>, String> = Result::new_ok(vec![1, 2, 3]);
+        assert_eq!(res.as_ok_slice(), Some(&[1, 2, 3][..]));
+
+        let res: Result<Vec<u8>, String> = Result::new_err("e".to_string());
+        assert_eq!(res.as_ok_slice::<u8>(), None);
+    }
+
+    #[test]
+    fn err_to_cstring_replaces_interior_nul() {
+        let res: Result<u8, &str> = Result::new_err("a\0b");
+        let text = res.err_to_cstring().unwrap();
+        assert_eq!(text.to_str(), Ok("a\u{FFFD}b"));
+
+        let res: Result<u8, &str> = Result::new_ok(1);
+        assert_eq!(res.err_to_cstring(), None);
+    }
+
+    #[test]
+    fn ok_to_cstring_replaces_interior_nul() {
+        let res: Result<&str, u8> = Result::new_ok("a\0b");
+        let text = res.ok_to_cstring().unwrap();
+        assert_eq!(text.to_str(), Ok("a\u{FFFD}b"));
+
+        let res: Result<&str, u8> = Result::new_ok("plain");
+        assert_eq!(res.ok_to_cstring(), Some(c"plain".to_owned()));
+
+        let res: Result<&str, u8> = Result::new_err(1);
+        assert_eq!(res.ok_to_cstring(), None);
+    }
+
+    #[test]
+    fn map_while_ok_stops_at_first_err() {
+        let items = [
+            Result::new_ok(1),
+            Result::new_ok(2),
+            Result::new_err("e"),
+            Result::new_ok(3),
+        ];
+        assert_eq!(map_while_ok(items).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn flatten_err_collapses_both_inner_variants() {
+        type Nested = Result<u8, Result<&'static str, &'static str>>;
+        assert_eq!(
+            Nested::new_err(Result::new_ok("a")).flatten_err(),
+            Result::new_err("a")
+        );
+        assert_eq!(
+            Nested::new_err(Result::new_err("b")).flatten_err(),
+            Result::new_err("b")
+        );
+        assert_eq!(Nested::new_ok(1).flatten_err(), Result::new_ok(1));
+    }
+
+    #[test]
+    fn assert_ok_returns_ok_value() {
+        assert_eq!(assert_ok!(Result::<u8, &str>::new_ok(3)), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected Ok, got Err(\"boom\")")]
+    fn assert_ok_panics_with_err_payload() {
+        assert_ok!(Result::<u8, &str>::new_err("boom"));
+    }
+
+    #[test]
+    fn assert_err_returns_error() {
+        assert_eq!(assert_err!(Result::<u8, &str>::new_err("boom")), "boom");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected Err, got Ok(3)")]
+    fn assert_err_panics_with_ok_payload() {
+        assert_err!(Result::<u8, &str>::new_ok(3));
+    }
+
+    const _: () = assert!(Result::<u8, u32>::max_payload_size() == 4);
+    const _: () = assert!(Result::<(), [u8; 5]>::max_payload_size() == 5);
+    const _: () = assert!(Result::<u64, ()>::max_payload_size() == 8);
+    #[cfg(not(feature = "checked"))]
+    const _: () = assert!(Result::<u8, u32>::total_size() == 8);
+    #[cfg(not(feature = "checked"))]
+    const _: () = assert!(Result::<(), [u8; 5]>::total_size() == 6);
+    #[cfg(feature = "checked")]
+    const _: () = assert!(Result::<u8, u32>::total_size() == 12);
+    #[cfg(feature = "checked")]
+    const _: () = assert!(Result::<(), [u8; 5]>::total_size() == 7);
+
+    #[test]
+    fn from_fn_wraps_both_outcomes() {
+        assert_eq!(Result::from_fn(|| "4".parse::<u8>()), Result::new_ok(4));
+        assert!(Result::from_fn(|| "x".parse::<u8>()).is_err());
+    }
+
+    #[test]
+    fn stringify_err_formats_error_only() {
+        let res: Result<u8, InvalidTag> = Result::new_err(InvalidTag(9));
+        assert_eq!(
+            res.stringify_err(),
+            Result::new_err("invalid result tag: 9".to_string())
+        );
+
+        let res: Result<u8, InvalidTag> = Result::new_ok(1);
+        assert_eq!(res.stringify_err(), Result::new_ok(1));
+    }
+
+    #[test]
+    fn clone_if_ok_needs_only_ok_clone() {
+        struct NoClone;
+
+        let res: Result<String, NoClone> = Result::new_ok("a".to_string());
+        let copy = res.clone_if_ok().unwrap();
+        assert_eq!(copy.ok().as_deref(), Some("a"));
+
+        let res: Result<String, NoClone> = Result::new_err(NoClone);
+        assert!(res.clone_if_ok().is_none());
+
+        let res: Result<NoClone, u8> = Result::new_err(2);
+        assert_eq!(res.clone_if_err().unwrap().err(), Some(2));
+        let res: Result<NoClone, u8> = Result::new_ok(NoClone);
+        assert!(res.clone_if_err().is_none());
+    }
+
+    #[test]
+    fn convert_widens_both_payloads() {
+        let res: Result<u32, u64> = Result::<u8, u16>::new_ok(7).convert();
+        assert_eq!(res, Result::new_ok(7));
+        let res: Result<u32, u64> = Result::<u8, u16>::new_err(300).convert();
+        assert_eq!(res, Result::new_err(300));
+    }
+
+    #[test]
+    fn debug_kind_prints_non_debug_payloads() {
+        struct Opaque;
+
+        let res: Result<Opaque, Opaque> = Result::new_ok(Opaque);
+        assert_eq!(format!("{:?}", res.debug_kind()), "Ok");
+        let res: Result<Opaque, Opaque> = Result::new_err(Opaque);
+        assert_eq!(format!("{:?}", res.debug_kind()), "Err");
+    }
+
+    #[test]
+    fn log_ok_and_log_err_call_logger_for_matching_variant() {
+        let calls = Cell::new(0);
+        let logger = |_: &u8| calls.set(calls.get() + 1);
+
+        let ok: Result<u8, u8> = Result::new_ok(1);
+        let ok = ok.log_ok(&logger).log_err(&logger);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(ok, Result::new_ok(1));
+
+        let err: Result<u8, u8> = Result::new_err(2);
+        let err = err.log_ok(&logger).log_err(&logger);
+        assert_eq!(calls.get(), 2);
+        assert_eq!(err, Result::new_err(2));
+    }
+
+    #[test]
+    fn status_code_round_trip() {
+        let cases = [
+            (Result::new_ok(0), 0),
+            (Result::new_ok(42), 42),
+            (Result::new_err(0), -1),
+            (Result::new_err(i64::MAX), i64::MIN),
+        ];
+        for (res, code) in cases {
+            assert_eq!(res.clone().into_code(), code);
+            assert_eq!(Result::from_code(code), res);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be encoded as a status code")]
+    fn into_code_rejects_negative_payload() {
+        let _ = Result::<i64, i64>::new_err(-1).into_code();
+    }
+
+    #[test]
+    fn ok_map_or_uses_default_on_err_or_decline() {
+        let ok = || Result::<u8, &str>::new_ok(4);
+        assert_eq!(ok().ok_map_or(0, |v| Some(v * 2)), 8);
+        assert_eq!(ok().ok_map_or(0, |_| None), 0);
+        assert_eq!(
+            Result::<u8, &str>::new_err("e").ok_map_or(0, |v| Some(v * 2)),
+            0
+        );
+    }
+
+    #[test]
+    fn as_ref_copy_err_borrows_ok_and_copies_err() {
+        let res: Result<String, i32> = Result::new_ok("a".to_string());
+        assert_eq!(res.as_ref_copy_err().ok().map(String::as_str), Some("a"));
+
+        let res: Result<String, i32> = Result::new_err(-2);
+        assert_eq!(res.as_ref_copy_err().err(), Some(-2));
+        assert_eq!(res.err(), Some(-2));
+    }
+
+    #[test]
+    fn take_leaves_ok_default() {
+        let mut res: Result<String, u8> = Result::new_err(3);
+        assert_eq!(res.take(), Result::new_err(3));
+        assert_eq!(res, Result::new_ok(String::new()));
+
+        let mut res: Result<String, u8> = Result::new_ok("a".to_string());
+        assert_eq!(res.take(), Result::new_ok("a".to_string()));
+        assert_eq!(res, Result::new_ok(String::new()));
+    }
+
+    #[test]
+    fn ok_mut_and_err_mut_mutate_in_place() {
+        let mut res: Result<u8, String> = Result::new_ok(1);
+        *res.ok_mut().unwrap() += 1;
+        assert!(res.err_mut().is_none());
+        assert_eq!(res, Result::new_ok(2));
+
+        let mut res: Result<u8, String> = Result::new_err("a".to_string());
+        res.err_mut().unwrap().push('b');
+        assert!(res.ok_mut().is_none());
+        assert_eq!(res, Result::new_err("ab".to_string()));
+    }
+
+    #[test]
+    fn eq_by_compares_floats_with_tolerance() {
+        let close = |a: &f64, b: &f64| (a - b).abs() < 1e-9;
+        let ok = |v: f64| Result::<f64, f64>::new_ok(v);
+        let err = |v: f64| Result::<f64, f64>::new_err(v);
+
+        assert!(ok(0.1 + 0.2).eq_by(&ok(0.3), close, close));
+        assert!(!ok(0.1).eq_by(&ok(0.3), close, close));
+        assert!(err(1.0).eq_by(&err(1.0 + 1e-12), close, close));
+        assert!(!ok(1.0).eq_by(&err(1.0), close, close));
+    }
+
+    #[test]
+    fn map_or_else_try_all_combinations_drop_once() {
+        let drops = Rc::new(Cell::new(0));
+        let ok = || Result::<Counted, Counted>::new_ok(Counted(drops.clone()));
+        let err = || Result::<Counted, Counted>::new_err(Counted(drops.clone()));
+
+        let res = ok().map_or_else_try(|_| unreachable!(), |_| Result::new_ok(1));
+        assert_eq!(res.ok(), Some(1));
+        assert_eq!(drops.get(), 1);
+
+        let res: Result<u8, _> = ok().map_or_else_try(|_| unreachable!(), Result::new_err);
+        assert!(res.is_err());
+        assert_eq!(drops.get(), 1);
+        drop(res);
+        assert_eq!(drops.get(), 2);
+
+        let res = err().map_or_else_try(|_| Result::new_ok(2), |_| unreachable!());
+        assert_eq!(res.ok(), Some(2));
+        assert_eq!(drops.get(), 3);
+
+        let res: Result<u8, _> = err().map_or_else_try(Result::new_err, |_| unreachable!());
+        assert!(res.is_err());
+        assert_eq!(drops.get(), 3);
+        drop(res);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn contains_checks_matching_variant_only() {
+        let ok: Result<u8, u8> = Result::new_ok(1);
+        let err: Result<u8, u8> = Result::new_err(1);
+
+        assert!(ok.contains(&1));
+        assert!(!ok.contains(&2));
+        assert!(!ok.contains_err(&1));
+        assert!(err.contains_err(&1));
+        assert!(!err.contains_err(&2));
+        assert!(!err.contains(&1));
+    }
+
+    #[test]
+    fn collect_indexed_errs_reports_error_index() {
+        let items = [Result::new_ok(1), Result::new_err("e"), Result::new_ok(3)];
+        assert_eq!(collect_indexed_errs(items), Err((1, "e")));
+
+        let items = [Result::<u8, &str>::new_ok(1), Result::new_ok(2)];
+        assert_eq!(collect_indexed_errs(items), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn split_moves_out_active_payload() {
+        let res: Result<u8, String> = Result::new_ok(1);
+        assert_eq!(res.split(), (Some(1), None));
+        let res: Result<u8, String> = Result::new_err("e".to_string());
+        assert_eq!(res.split(), (None, Some("e".to_string())));
+    }
+
+    #[test]
+    fn ok_flatten_or_handles_all_states() {
+        let res: Result<Option<u8>, &str> = Result::new_ok(Some(1));
+        assert_eq!(res.ok_flatten_or(9), Result::new_ok(1));
+        let res: Result<Option<u8>, &str> = Result::new_ok(None);
+        assert_eq!(res.ok_flatten_or(9), Result::new_ok(9));
+        let res: Result<Option<u8>, &str> = Result::new_err("e");
+        assert_eq!(res.ok_flatten_or(9), Result::new_err("e"));
+    }
+
+    #[test]
+    fn collect_chunked_groups_and_stops_at_err() {
+        let items = (1..=5).map(Result::<u8, &str>::new_ok);
+        assert_eq!(
+            collect_chunked(items, 2),
+            Ok(vec![vec![1, 2], vec![3, 4], vec![5]])
+        );
+
+        let items = [Result::new_ok(1), Result::new_err("e"), Result::new_ok(3)];
+        assert_eq!(collect_chunked(items, 2), Err("e"));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn collect_chunked_rejects_zero_chunk() {
+        let _ = collect_chunked([Result::<u8, u8>::new_ok(1)], 0);
+    }
+
+    #[test]
+    fn erase_err_boxes_custom_error() {
+        #[derive(Debug, PartialEq)]
+        struct Custom;
+        impl Display for Custom {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "custom")
+            }
+        }
+        impl std::error::Error for Custom {}
+
+        let res: Result<u8, Custom> = Result::new_err(Custom);
+        let err = res.erase_err().unwrap_err();
+        assert_eq!(err.to_string(), "custom");
+        assert_eq!(err.downcast_ref::<Custom>(), Some(&Custom));
+
+        let res: Result<u8, Custom> = Result::new_ok(1);
+        assert_eq!(res.erase_err().ok(), Some(1));
+    }
+
+    #[test]
+    fn assert_same_layout_accepts_matching_instantiations() {
+        Result::<u32, u8>::assert_same_layout::<i32, u8>();
+        Result::<u64, ()>::assert_same_layout::<f64, ()>();
+        Result::<String, u8>::assert_same_layout::<Vec<u8>, u8>();
+        Result::<Box<u8>, usize>::assert_same_layout::<&u8, isize>();
+    }
+
+    #[test]
+    fn accept_tallies_with_visitor() {
+        #[derive(Default)]
+        struct Tally {
+            oks: u32,
+            errs: u32,
+            sum: u32,
+        }
+        impl Visitor<u32, &str> for Tally {
+            fn on_ok(&mut self, ok: &u32) {
+                self.oks += 1;
+                self.sum += ok;
+            }
+            fn on_err(&mut self, _: &&str) {
+                self.errs += 1;
+            }
+        }
+
+        let items = [Result::new_ok(2), Result::new_err("e"), Result::new_ok(5)];
+        let mut tally = Tally::default();
+        items.iter().for_each(|res| res.accept(&mut tally));
+        assert_eq!((tally.oks, tally.errs, tally.sum), (2, 1, 7));
+    }
+
+    #[test]
+    fn map_err_dyn_uses_mapper_chosen_at_runtime() {
+        let double = |e: i32| e * 2;
+        let negate = |e: i32| -e;
+        let mappers: [&dyn Fn(i32) -> i32; 2] = [&double, &negate];
+
+        for (mapper, expected) in mappers.into_iter().zip([6, -3]) {
+            let res: Result<u8, i32> = Result::new_err(3);
+            assert_eq!(res.map_err_dyn(mapper), Result::new_err(expected));
+        }
+        let res: Result<u8, i32> = Result::new_ok(1);
+        assert_eq!(res.map_err_dyn(mappers[0]), Result::new_ok(1));
+    }
+
+    #[test]
+    fn with_capacity_constructors_reserve() {
+        let res: Result<Vec<u16>, ()> = Result::new_ok_vec_with_capacity(32);
+        let ok = res.as_ref().ok().unwrap();
+        assert!(ok.is_empty() && ok.capacity() >= 32);
+
+        let res: Result<String, ()> = Result::new_ok_string_with_capacity(32);
+        let ok = res.as_ref().ok().unwrap();
+        assert!(ok.is_empty() && ok.capacity() >= 32);
+    }
+
+    #[test]
+    fn poll_fn_ffi_converts_ready_results() {
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+
+        let mut polls = 0;
+        let fut = poll_fn_ffi(|_| {
+            polls += 1;
+            if polls == 1 {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok::<u8, &str>(4))
+            }
+        });
+        let mut fut = std::pin::pin!(fut);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Result::new_ok(4)));
+
+        let fut = std::pin::pin!(poll_fn_ffi(|_| Poll::Ready(Err::<u8, &str>("e"))));
+        assert_eq!(fut.poll(&mut cx), Poll::Ready(Result::new_err("e")));
+    }
+
+    #[test]
+    fn into_shared_reads_from_two_threads() {
+        let shared = Result::<String, u8>::new_ok("shared".to_string()).into_shared();
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                let shared = Arc::clone(&shared);
+                scope.spawn(move || {
+                    assert_eq!(shared.ok_or_err_ref().map(String::as_str), Ok("shared"));
+                });
+            }
+        });
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn err_as_i32_maps_repr_enum_to_code() {
+        #[repr(i32)]
+        #[derive(Clone, Copy)]
+        enum Errno {
+            NotFound = 2,
+            Denied = 13,
+        }
+        impl From<Errno> for i32 {
+            fn from(err: Errno) -> i32 {
+                err as i32
+            }
+        }
+
+        let res: Result<u8, Errno> = Result::new_err(Errno::NotFound);
+        assert_eq!(res.err_as_i32(), Result::new_err(2));
+        let res: Result<u8, Errno> = Result::new_err(Errno::Denied);
+        assert_eq!(res.err_as_i32(), Result::new_err(13));
+        let res: Result<u8, Errno> = Result::new_ok(1);
+        assert_eq!(res.err_as_i32(), Result::new_ok(1));
+    }
+
+    #[test]
+    fn apply_all_mutates_oks_and_skips_errs() {
+        let mut results = [Result::new_ok(1), Result::new_err(10), Result::new_ok(2)];
+        apply_all(&mut results, |ok| *ok *= 3);
+        assert_eq!(
+            results,
+            [Result::new_ok(3), Result::new_err(10), Result::new_ok(6)]
+        );
+    }
+
+    #[test]
+    fn into_nullable_ptr_transfers_box_ownership() {
+        let drops = Rc::new(Cell::new(0));
+        let res: Result<Box<Counted>, u8> = Result::new_ok(Box::new(Counted(drops.clone())));
+        let (ptr, err) = res.into_nullable_ptr();
+        assert!(!ptr.is_null());
+        assert_eq!(err, 0);
+        assert_eq!(drops.get(), 0);
+        drop(unsafe { Box::from_raw(ptr) });
+        assert_eq!(drops.get(), 1);
+
+        let res: Result<Box<Counted>, u8> = Result::new_err(5);
+        let (ptr, err) = res.into_nullable_ptr();
+        assert!(ptr.is_null());
+        assert_eq!(err, 5);
+    }
+
+    #[test]
+    fn is_ffi_ok_and_err_accept_references_without_consuming() {
+        let res: Result<String, u8> = Result::new_ok("a".to_string());
+        let r = &res;
+        assert!(is_ffi_ok!(r));
+        assert!(!is_ffi_err!(r));
+        assert!(is_ffi_ok!(res));
+        assert_eq!(res.ok().as_deref(), Some("a"));
+
+        let res: Result<String, u8> = Result::new_err(1);
+        let r = &res;
+        assert!(is_ffi_err!(r));
+        assert!(!is_ffi_ok!(r));
+        assert!(is_ffi_err!(res));
+        assert_eq!(res.err(), Some(1));
+    }
+
+    #[test]
+    fn take_ok_until_err_leaves_trailing_items() {
+        let items = [
+            Result::new_ok(1),
+            Result::new_ok(2),
+            Result::new_err("e"),
+            Result::new_ok(3),
+        ];
+        let mut iter = items.into_iter();
+        assert_eq!(take_ok_until_err(&mut iter), (vec![1, 2], Some("e")));
+        assert_eq!(iter.next(), Some(Result::new_ok(3)));
+        assert_eq!(iter.next(), None);
+
+        let items = [Result::<u8, &str>::new_ok(1)];
+        assert_eq!(take_ok_until_err(items), (vec![1], None));
+    }
+
+    #[test]
+    fn combine_with_adds_oks_and_drops_discarded_value() {
+        let ok = Result::<u8, &str>::new_ok;
+        assert_eq!(ok(2).combine_with(ok(3), |a, b| a + b), Result::new_ok(5));
+        assert_eq!(
+            ok(2).combine_with(Result::<u8, _>::new_err("b"), |a, b| a + b),
+            Result::new_err("b")
+        );
+        assert_eq!(
+            Result::<u8, _>::new_err("a").combine_with(Result::<u8, _>::new_err("b"), |a, b| a + b),
+            Result::new_err("a")
+        );
+
+        let drops = Rc::new(Cell::new(0));
+        let res = Result::<Counted, &str>::new_err("a")
+            .combine_with(Result::new_ok(Counted(drops.clone())), |_, _: Counted| ());
+        assert_eq!(res, Result::new_err("a"));
+        assert_eq!(drops.get(), 1);
+
+        let res = Result::new_ok(Counted(drops.clone()))
+            .combine_with(Result::<u8, _>::new_err("b"), |_, _| ());
+        assert_eq!(res, Result::new_err("b"));
+        assert_eq!(drops.get(), 2);
+    }
+
+    // `Result` has a destructor, which cannot run in const context, so forget the values.
+    const _: () = {
+        let ok = Result::<(), ()>::new_ok(());
+        let err = Result::<u64, u8>::new_err(7);
+        assert!(ok.discriminant() == 0);
+        assert!(err.discriminant() == 1);
+        std::mem::forget(ok);
+        std::mem::forget(err);
+    };
+
+    #[test]
+    fn try_reserve_ok_reserves_on_ok_only() {
+        let mut res: Result<Vec<u8>, String> = Result::new_ok(Vec::new());
+        assert!(res.try_reserve_ok(16).is_ok());
+        assert!(res.as_ref().ok().unwrap().capacity() >= 16);
+
+        let mut res: Result<Vec<u8>, String> = Result::new_err("e".to_string());
+        assert!(res.try_reserve_ok(usize::MAX).is_ok());
+        assert_eq!(res, Result::new_err("e".to_string()));
+    }
+
+    #[test]
+    fn collect_cloned_from_borrowed_slice() {
+        let results = [
+            Result::<String, String>::new_ok("a".to_string()),
+            Result::new_ok("b".to_string()),
+        ];
+        assert_eq!(
+            collect_cloned(results.iter().map(Result::as_ref)),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+
+        let results = [
+            Result::<String, String>::new_ok("a".to_string()),
+            Result::new_err("e".to_string()),
+        ];
+        assert_eq!(
+            collect_cloned(results.iter().map(Result::as_ref)),
+            Err("e".to_string())
+        );
+    }
+
+    #[test]
+    fn map_both_ref_maps_each_variant() {
+        let res: Result<String, u8> = Result::new_ok("abc".to_string());
+        assert_eq!(res.map_both_ref(String::len, |e| *e + 1), Ok(3));
+        let res: Result<String, u8> = Result::new_err(4);
+        assert_eq!(res.map_both_ref(String::len, |e| *e + 1), Err(5));
+        assert_eq!(res.err(), Some(4));
+    }
+
+    #[test]
+    fn collect_unique_errs_ignores_interleaved_oks() {
+        let items = [
+            Result::new_err("a"),
+            Result::new_ok(1),
+            Result::new_err("a"),
+            Result::new_ok(2),
+            Result::new_err("b"),
+            Result::new_err("a"),
+        ];
+        assert_eq!(collect_unique_errs(items), vec!["a", "b", "a"]);
+    }
+
+    #[test]
+    fn ok_as_ptr_or_null_points_at_ok_payload() {
+        let res: Result<u32, u8> = Result::new_ok(9);
+        let ptr = res.ok_as_ptr_or_null();
+        assert!(!ptr.is_null());
+        assert_eq!(unsafe { *ptr }, 9);
+        assert!(std::ptr::eq(ptr, res.as_ref().ok().unwrap()));
+
+        let res: Result<u32, u8> = Result::new_err(1);
+        assert!(res.ok_as_ptr_or_null().is_null());
+    }
+
+    #[test]
+    fn scan_ok_sums_until_error_and_drops_rest() {
+        let items = (1..=4).map(Result::<u32, &str>::new_ok);
+        let res = scan_ok(items, 0, |sum, n| {
+            *sum += n;
+            Result::new_ok(())
+        });
+        assert_eq!(res, Result::new_ok(10));
+
+        let drops = Rc::new(Cell::new(0));
+        let item = |n: u32| Result::<(u32, Counted), &str>::new_ok((n, Counted(drops.clone())));
+        let items = vec![item(1), item(2), Result::new_err("e"), item(3), item(4)];
+        let mut seen = 0;
+        let res = scan_ok(items, 0, |sum, (n, _)| {
+            seen += 1;
+            *sum += n;
+            Result::new_ok(())
+        });
+        assert_eq!(res, Result::new_err("e"));
+        assert_eq!(seen, 2);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn ok_or_err_ref_borrows_active_payload() {
+        let res: Result<String, u8> = Result::new_ok("a".to_string());
+        assert_eq!(res.ok_or_err_ref(), Ok(&"a".to_string()));
+        let res: Result<String, u8> = Result::new_err(2);
+        assert_eq!(res.ok_or_err_ref(), Err(&2));
+        assert_eq!(res.err(), Some(2));
+    }
+
+    #[test]
+    fn unwrap_or_report_returns_ok_without_reporting() {
+        let reported = Cell::new(false);
+        let res: Result<u8, &str> = Result::new_ok(3);
+        assert_eq!(res.unwrap_or_report(|_| reported.set(true)), 3);
+        assert!(!reported.get());
+    }
+
+    #[test]
+    fn into_maybe_uninit_initializes_slot_for_tag() {
+        let res: Result<String, u8> = Result::new_ok("a".to_string());
+        let (tag, ok, _) = res.into_maybe_uninit();
+        assert_eq!(tag, 0);
+        assert_eq!(unsafe { ok.assume_init() }, "a");
+
+        let res: Result<String, u8> = Result::new_err(7);
+        let (tag, _, err) = res.into_maybe_uninit();
+        assert_eq!(tag, 1);
+        assert_eq!(unsafe { err.assume_init() }, 7);
+    }
+
+    #[test]
+    fn result_cache_runs_init_once() {
+        let mut cache: ResultCache<u8, &str> = ResultCache::new();
+        let calls = Cell::new(0);
+        let init = || {
+            calls.set(calls.get() + 1);
+            Result::new_ok(4)
+        };
+
+        assert_eq!(cache.get_or_init(init), &Result::new_ok(4));
+        assert_eq!(cache.get_or_init(init), &Result::new_ok(4));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.take(), Some(Result::new_ok(4)));
+        assert_eq!(cache.get(), None);
+    }
+
+    #[test]
+    fn as_slice_has_one_element_for_ok_only() {
+        let mut res: Result<u8, &str> = Result::new_ok(1);
+        assert_eq!(res.as_slice(), &[1]);
+        res.as_mut_slice()[0] = 2;
+        assert_eq!(res.as_mut_slice().len(), 1);
+        assert_eq!(res, Result::new_ok(2));
+
+        let mut res: Result<u8, &str> = Result::new_err("e");
+        assert!(res.as_slice().is_empty());
+        assert!(res.as_mut_slice().is_empty());
+    }
+
+    #[test]
+    fn eq_ignore_err_treats_all_errors_as_equal() {
+        let ok = Result::<u8, &str>::new_ok;
+        let err = Result::<u8, &str>::new_err;
+
+        assert!(ok(1).eq_ignore_err(&ok(1)));
+        assert!(!ok(1).eq_ignore_err(&ok(2)));
+        assert!(err("a").eq_ignore_err(&err("b")));
+        assert!(!ok(1).eq_ignore_err(&err("a")));
+        assert!(!err("a").eq_ignore_err(&ok(1)));
+    }
+
+    #[test]
+    fn hash_by_ok_collapses_errors_into_one_key() {
+        use std::collections::HashMap;
+
+        let results = [
+            Result::new_ok(1),
+            Result::new_err("a"),
+            Result::new_ok(1),
+            Result::new_err("b"),
+            Result::new_ok(2),
+        ];
+        let mut counts = HashMap::new();
+        for res in &results {
+            *counts.entry(HashByOk(res)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&HashByOk(&Result::new_ok(1))], 2);
+        assert_eq!(counts[&HashByOk(&Result::new_err("c"))], 2);
+    }
+
+    #[test]
+    fn pipe_feeds_result_to_closure() {
+        let res: Result<u8, &str> = Result::new_ok(1);
+        assert_eq!(res.pipe(|r| r.into_result()), Ok(1));
+        let res: Result<u8, &str> = Result::new_err("e");
+        assert_eq!(res.pipe(|r| r.into_result()), Err("e"));
+    }
+
+    #[test]
+    fn map_or_abort_maps_ok() {
+        let res: Result<u8, &str> = Result::new_ok(2);
+        assert_eq!(res.map_or_abort(|v| v * 5), 10);
+    }
+
+    #[test]
+    fn err_sources_walks_source_chain() {
+        #[derive(Debug)]
+        struct Outer(InvalidTag);
+        impl Display for Outer {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "outer")
+            }
+        }
+        impl std::error::Error for Outer {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let res: Result<u8, Outer> = Result::new_err(Outer(InvalidTag(3)));
+        let chain: Vec<String> = res.err_sources().map(|err| err.to_string()).collect();
+        assert_eq!(chain, ["outer", "invalid result tag: 3"]);
+
+        let res: Result<u8, Outer> = Result::new_ok(1);
+        assert_eq!(res.err_sources().count(), 0);
+    }
+
+    #[test]
+    fn err_to_cow_borrows_marker_for_ok() {
+        let res: Result<u8, InvalidTag> = Result::new_ok(1);
+        assert!(matches!(res.err_to_cow(), Cow::Borrowed("ok")));
+
+        let res: Result<u8, InvalidTag> = Result::new_err(InvalidTag(4));
+        let text = res.err_to_cow();
+        assert!(matches!(text, Cow::Owned(_)));
+        assert_eq!(text, "invalid result tag: 4");
+    }
+
+    #[test]
+    fn try_map_err_recovers_or_refails() {
+        let recover = |err: &str| match err.parse::<u8>() {
+            Ok(n) => Result::new_ok(n),
+            Err(_) => Result::new_err(err.len()),
+        };
+
+        assert_eq!(Result::new_err("7").try_map_err(recover), Result::new_ok(7));
+        assert_eq!(
+            Result::new_err("bad").try_map_err(recover),
+            Result::new_err(3)
+        );
+        assert_eq!(Result::new_ok(1).try_map_err(recover), Result::new_ok(1));
+    }
+
+    #[test]
+    fn self_check_passes() {
+        assert!(self_check());
+    }
+
+    #[test]
+    fn map_or_log_err_logs_only_errors() {
+        let logged = Cell::new(0);
+        let log = |_: &&str| logged.set(logged.get() + 1);
+
+        let res: Result<u8, &str> = Result::new_ok(2);
+        assert_eq!(res.map_or_log_err(|v| v + 1, log), Some(3));
+        assert_eq!(logged.get(), 0);
+
+        let res: Result<u8, &str> = Result::new_err("e");
+        assert_eq!(res.map_or_log_err(|v| v + 1, log), None);
+        assert_eq!(logged.get(), 1);
+    }
+
+    #[test]
+    fn into_boxed_pair_boxes_active_payload() {
+        let drops = Rc::new(Cell::new(0));
+
+        let res: Result<Counted, Counted> = Result::new_ok(Counted(drops.clone()));
+        let (ok, err) = res.into_boxed_pair();
+        assert!(err.is_none());
+        let ptr = Box::into_raw(ok.unwrap());
+        assert_eq!(drops.get(), 0);
+        drop(unsafe { Box::from_raw(ptr) });
+        assert_eq!(drops.get(), 1);
+
+        let res: Result<Counted, Counted> = Result::new_err(Counted(drops.clone()));
+        let (ok, err) = res.into_boxed_pair();
+        assert!(ok.is_none());
+        let ptr = Box::into_raw(err.unwrap());
+        assert_eq!(drops.get(), 1);
+        drop(unsafe { Box::from_raw(ptr) });
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn borrowed_constructors_convert_to_std() {
+        let value = 5u8;
+        let res: Result<&u8, String> = Result::new_ok_ref(&value);
+        assert_eq!(res.into_result(), Ok(&5));
+
+        let err = "e".to_string();
+        let res: Result<u8, &String> = Result::new_err_ref(&err);
+        assert_eq!(res.into_result(), Err(&err));
+    }
+
+    #[test]
+    fn first_err_index_finds_first_error() {
+        let results = [
+            Result::new_ok(1),
+            Result::new_err("a"),
+            Result::new_err("b"),
+        ];
+        assert_eq!(first_err_index(&results), Some(1));
+
+        let results = [Result::<u8, &str>::new_ok(1), Result::new_ok(2)];
+        assert_eq!(first_err_index(&results), None);
+        assert_eq!(first_err_index::<u8, &str>(&[]), None);
+    }
+}
+
+#[cfg(test)]
+mod prelude_tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn prelude_does_not_shadow_std_result() {
+        let ffi: FfiResult<u8, &str> = FfiResult::new_ok(1);
+        let std: Result<u8, &str> = Ok(1);
+        assert_eq!(ffi.into_result(), std);
+        assert_eq!(*FfiResult::<u8, u8>::new_err(2).kind(), ResultKind::Err);
+    }
+}
+
+#[cfg(all(test, feature = "checked"))]
+mod checked_tests {
+    use super::*;
+
+    fn corrupted(drops: &Rc<Cell<usize>>) -> Result<Counted, Counted> {
+        let mut res = Result::new_ok(Counted(drops.clone()));
+        unsafe { *res.kind_mut() = ResultKind::Err };
+        res
+    }
+
+    #[test]
+    fn try_into_result_reports_corrupt_tag() {
+        let drops = Rc::new(Cell::new(0));
+        assert!(matches!(
+            corrupted(&drops).try_into_result(),
+            Err(CorruptTag)
+        ));
+        assert_eq!(drops.get(), 0);
+
+        let res: Result<u32, u32> = Result::new_ok(5);
+        assert_eq!(res.try_into_result(), Ok(Ok(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "result tag is inconsistent")]
+    fn into_result_asserts_consistency() {
+        let drops = Rc::new(Cell::new(0));
+        let _ = corrupted(&drops).into_result();
+    }
+
+    #[test]
+    fn payload_accessors_assert_consistency() {
+        let drops = Rc::new(Cell::new(0));
+        let res = corrupted(&drops);
+        let panics =
+            |f: &dyn Fn()| std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err();
+
+        assert!(panics(&|| _ = res.as_ref()));
+        assert!(panics(&|| _ = res.ok_or_err_ref()));
+        assert!(panics(&|| _ = corrupted(&drops).ok()));
+        assert!(panics(&|| _ = corrupted(&drops).err()));
+        assert!(panics(&|| _ = corrupted(&drops).split()));
+        assert!(res.is_err());
+        assert_eq!(drops.get(), 0);
+
+        let mut res: Result<u32, u32> = Result::new_ok(5);
+        unsafe { *res.kind_mut() = ResultKind::Err };
+        assert!(panics(&|| _ = res.clone()));
+        assert!(panics(&|| _ = format!("{res:?}")));
+    }
+
+    #[test]
+    fn drop_leaks_inconsistent_payload() {
+        let drops = Rc::new(Cell::new(0));
+        drop(corrupted(&drops));
+        assert_eq!(drops.get(), 0);
+
+        drop(Result::<Counted, Counted>::new_ok(Counted(drops.clone())));
+        assert_eq!(drops.get(), 1);
+    }
+
+    fn relabeled() -> Result<String, u8> {
+        unsafe { Result::new_ok("payload".to_string()).reinterpret_kind(ResultKind::Err) }
+    }
+
+    #[test]
+    fn reinterpret_kind_yields_corrupt_tag() {
+        assert!(matches!(relabeled().try_into_result(), Err(CorruptTag)));
+    }
+
+    #[test]
+    #[should_panic(expected = "result tag is inconsistent")]
+    fn reinterpret_kind_trips_consistency_assertion() {
+        let _ = relabeled().into_result();
+    }
 }